@@ -0,0 +1,12 @@
+extern crate bls_sigs_ref;
+extern crate bls_sigs_test;
+
+use bls_sigs_test::{get_vecs, test_derive_master};
+use std::io::Result;
+
+fn main() -> Result<()> {
+    for vec in get_vecs("eip2333_master")? {
+        test_derive_master(vec?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,12 @@
+extern crate bls_sigs_ref;
+extern crate bls_sigs_test;
+
+use bls_sigs_test::{get_files, test_keystore, EIP2335_PASSWORD, EIP2335_SECRET};
+use std::io::Result;
+
+fn main() -> Result<()> {
+    for json in get_files("eip2335")? {
+        test_keystore(&json, EIP2335_PASSWORD, &EIP2335_SECRET)?;
+    }
+    Ok(())
+}
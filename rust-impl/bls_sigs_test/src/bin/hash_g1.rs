@@ -8,7 +8,7 @@ use std::io::Result;
 
 fn main() -> Result<()> {
     for vec in get_vecs("hash_g1")? {
-        test_hash::<G1>(vec?, &[1u8], 48)?;
+        test_hash::<G1>(vec?, &[1u8])?;
     }
     Ok(())
 }
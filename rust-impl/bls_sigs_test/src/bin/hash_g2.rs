@@ -8,7 +8,7 @@ use std::io::Result;
 
 fn main() -> Result<()> {
     for vec in get_vecs("hash_g2")? {
-        test_hash::<G2>(vec?, &[2u8], 96)?;
+        test_hash::<G2>(vec?, &[2u8])?;
     }
     Ok(())
 }
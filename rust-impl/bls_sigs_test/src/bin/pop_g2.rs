@@ -8,7 +8,7 @@ use std::io::Result;
 
 fn main() -> Result<()> {
     for vec in get_vecs("pop_g2")? {
-        test_pop::<G2>(vec?, 96)?;
+        test_pop::<G2>(vec?)?;
     }
     Ok(())
 }
@@ -0,0 +1,15 @@
+extern crate bls_sigs_ref;
+extern crate bls_sigs_test;
+extern crate pairing_plus;
+
+use bls_sigs_test::{get_named_vecs, test_sig_invalid};
+use pairing_plus::bls12_381::G1;
+use std::io::Result;
+
+fn main() -> Result<()> {
+    for vec in get_named_vecs("sig_g1_invalid")? {
+        let (name, vec) = vec?;
+        test_sig_invalid::<G1>(vec, &name)?;
+    }
+    Ok(())
+}
@@ -8,7 +8,7 @@ use std::io::Result;
 
 fn main() -> Result<()> {
     for vec in get_vecs("sig_g1_pop")? {
-        test_sig_pop::<G1>(vec?, 48)?;
+        test_sig_pop::<G1>(vec?)?;
     }
     Ok(())
 }
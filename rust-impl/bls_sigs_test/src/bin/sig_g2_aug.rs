@@ -8,7 +8,7 @@ use std::io::Result;
 
 fn main() -> Result<()> {
     for vec in get_vecs("sig_g2_aug")? {
-        test_sig_aug::<G2>(vec?, 96)?;
+        test_sig_aug::<G2>(vec?)?;
     }
     Ok(())
 }
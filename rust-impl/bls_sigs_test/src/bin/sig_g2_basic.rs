@@ -8,7 +8,7 @@ use std::io::Result;
 
 fn main() -> Result<()> {
     for vec in get_vecs("sig_g2_basic")? {
-        test_sig_basic::<G2>(vec?, 96)?;
+        test_sig_basic::<G2>(vec?)?;
     }
     Ok(())
 }
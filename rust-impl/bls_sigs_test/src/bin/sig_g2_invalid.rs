@@ -0,0 +1,15 @@
+extern crate bls_sigs_ref;
+extern crate bls_sigs_test;
+extern crate pairing_plus;
+
+use bls_sigs_test::{get_named_vecs, test_sig_invalid};
+use pairing_plus::bls12_381::G2;
+use std::io::Result;
+
+fn main() -> Result<()> {
+    for vec in get_named_vecs("sig_g2_invalid")? {
+        let (name, vec) = vec?;
+        test_sig_invalid::<G2>(vec, &name)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+extern crate bls_sigs_ref;
+extern crate bls_sigs_test;
+extern crate pairing_plus;
+
+use bls_sigs_test::{
+    test_timing_control, test_timing_core_sign, test_timing_keygen, TimingReport, T_THRESHOLD,
+};
+use pairing_plus::bls12_381::{G1, G2};
+use std::env::args;
+use std::process::exit;
+
+fn report(name: &str, result: TimingReport) -> bool {
+    println!(
+        "{:<16} samples {:>8}  max |t| {:>8.2}  {}",
+        name,
+        result.samples,
+        result.max_t,
+        if result.leaks() {
+            "secret-dependent timing"
+        } else {
+            "ok"
+        }
+    );
+    result.leaks()
+}
+
+fn main() {
+    let samples = args()
+        .nth(1)
+        .map(|a| a.parse().expect("usage: timing [samples]"))
+        .unwrap_or(20_000);
+    println!("dudect-style timing test, threshold |t| > {}", T_THRESHOLD);
+
+    let mut leaks = false;
+    leaks |= report("core_sign G1", test_timing_core_sign::<G1>(samples));
+    leaks |= report("core_sign G2", test_timing_core_sign::<G2>(samples));
+    leaks |= report("keygen G1", test_timing_keygen::<G1>(samples));
+    leaks |= report("keygen G2", test_timing_keygen::<G2>(samples));
+    // the control is expected to leak; it shows that the harness can see a leak on this machine
+    report("control G1", test_timing_control::<G1>(samples));
+    if leaks {
+        exit(1);
+    }
+}
@@ -1,5 +1,5 @@
-#![cfg_attr(feature = "cargo-clippy", deny(warnings))]
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::unreadable_literal))]
+#![cfg_attr(clippy, deny(warnings))]
+#![allow(clippy::unreadable_literal)]
 #![deny(missing_debug_implementations)]
 #![deny(missing_docs)]
 
@@ -8,41 +8,49 @@
 */
 
 extern crate bls_sigs_ref;
+extern crate ff_zeroize as ff;
 extern crate pairing_plus;
+extern crate rand_core;
+extern crate rand_xorshift;
 extern crate sha2;
 
 #[cfg(test)]
 mod test;
 mod testvec;
+mod timing;
 
-use bls_sigs_ref::{BLSSignatureAug, BLSSignatureBasic, BLSSignaturePop};
+use bls_sigs_ref::{
+    derive_child_sk, derive_master_sk, BLSSignatureAug, BLSSignatureBasic, BLSSignaturePop, Basic,
+    BlsError, Keystore, PointEncoding, Pop, SecretKey, Signature,
+};
+use ff::{PrimeField, PrimeFieldRepr};
+use pairing_plus::bls12_381::{Fr, FrRepr, G2};
 use pairing_plus::hash_to_curve::HashToCurve;
 use pairing_plus::hash_to_field::ExpandMsgXmd;
-use pairing_plus::serdes::SerDes;
-use pairing_plus::CurveProjective;
-use std::io::{Cursor, Result};
+use pairing_plus::{CurveAffine, CurveProjective, EncodedPoint};
 use sha2::Sha256;
-pub use testvec::{get_dflt_vecs, get_vecs, TestVector};
+use std::io::{Cursor, Error, ErrorKind, Result};
+pub use testvec::{
+    get_dflt_files, get_dflt_named_vecs, get_dflt_vecs, get_files, get_named_vecs, get_vecs,
+    TestVector,
+};
+pub use timing::{
+    test_timing, test_timing_control, test_timing_core_sign, test_timing_keygen, TimingReport,
+    T_THRESHOLD,
+};
 
 /// Test hash function
-pub fn test_hash<G>(tests: Vec<TestVector>, ciphersuite: &[u8], len: usize) -> Result<()>
+pub fn test_hash<G>(tests: Vec<TestVector>, ciphersuite: &[u8]) -> Result<()>
 where
-    G: CurveProjective + HashToCurve<ExpandMsgXmd<Sha256>> + SerDes,
+    G: CurveProjective + HashToCurve<ExpandMsgXmd<Sha256>> + PointEncoding,
 {
     for TestVector { msg, expect, .. } in tests {
         let result = G::hash_to_curve(&msg, ciphersuite);
         match expect {
             None => println!("{:?}", result),
             Some(e) => {
-                let mut buf = [0u8; 96];
-                {
-                    let mut cur = Cursor::new(&mut buf[..]);
-                    result.serialize(&mut cur, true)?;
-                }
-                assert_eq!(e.as_ref() as &[u8], &buf[..len]);
-
-                let deser = G::deserialize(&mut Cursor::new(&e), true)?;
-                assert_eq!(result, deser);
+                assert_eq!(&e[..], result.to_compressed().as_ref());
+                assert_eq!(G::from_compressed(&e[..]), Ok(result));
             }
         }
     }
@@ -50,9 +58,9 @@ where
 }
 
 /// Test sign functionality for Basic
-pub fn test_sig_basic<G>(tests: Vec<TestVector>, len: usize) -> Result<()>
+pub fn test_sig_basic<G>(tests: Vec<TestVector>) -> Result<()>
 where
-    G: BLSSignatureBasic<ExpandMsgXmd<Sha256>> + CurveProjective + SerDes,
+    G: BLSSignatureBasic<ExpandMsgXmd<Sha256>> + CurveProjective,
 {
     for TestVector { msg, sk, expect } in tests {
         let (x_prime, pk) = G::keygen(sk);
@@ -61,15 +69,8 @@ where
         match expect {
             None => println!("{:?}", sig),
             Some(e) => {
-                let mut buf = [0u8; 96];
-                {
-                    let mut cur = Cursor::new(&mut buf[..]);
-                    sig.serialize(&mut cur, true)?;
-                }
-                assert_eq!(e.as_ref() as &[u8], &buf[..len]);
-
-                let deser = G::deserialize(&mut Cursor::new(&e), true)?;
-                assert_eq!(sig, deser);
+                assert_eq!(&e[..], sig.to_compressed().as_ref());
+                assert_eq!(G::from_compressed(&e[..]), Ok(sig));
             }
         }
     }
@@ -77,9 +78,9 @@ where
 }
 
 /// Test sign functionality for Augmented
-pub fn test_sig_aug<G>(tests: Vec<TestVector>, len: usize) -> Result<()>
+pub fn test_sig_aug<G>(tests: Vec<TestVector>) -> Result<()>
 where
-    G: BLSSignatureAug<ExpandMsgXmd<Sha256>> + CurveProjective + SerDes,
+    G: BLSSignatureAug<ExpandMsgXmd<Sha256>> + CurveProjective,
 {
     for TestVector { msg, sk, expect } in tests {
         let (x_prime, pk) = G::keygen(sk);
@@ -88,15 +89,8 @@ where
         match expect {
             None => println!("{:?}", sig),
             Some(e) => {
-                let mut buf = [0u8; 96];
-                {
-                    let mut cur = Cursor::new(&mut buf[..]);
-                    sig.serialize(&mut cur, true)?;
-                }
-                assert_eq!(e.as_ref() as &[u8], &buf[..len]);
-
-                let deser = G::deserialize(&mut Cursor::new(&e), true)?;
-                assert_eq!(sig, deser);
+                assert_eq!(&e[..], sig.to_compressed().as_ref());
+                assert_eq!(G::from_compressed(&e[..]), Ok(sig));
             }
         }
     }
@@ -104,9 +98,9 @@ where
 }
 
 /// Test sign functionality for Pop
-pub fn test_sig_pop<G>(tests: Vec<TestVector>, len: usize) -> Result<()>
+pub fn test_sig_pop<G>(tests: Vec<TestVector>) -> Result<()>
 where
-    G: BLSSignaturePop<ExpandMsgXmd<Sha256>> + CurveProjective + SerDes,
+    G: BLSSignaturePop<ExpandMsgXmd<Sha256>> + CurveProjective,
 {
     for TestVector { msg, sk, expect } in tests {
         let (x_prime, pk) = G::keygen(sk);
@@ -115,15 +109,8 @@ where
         match expect {
             None => println!("{:?}", sig),
             Some(e) => {
-                let mut buf = [0u8; 96];
-                {
-                    let mut cur = Cursor::new(&mut buf[..]);
-                    sig.serialize(&mut cur, true)?;
-                }
-                assert_eq!(e.as_ref() as &[u8], &buf[..len]);
-
-                let deser = G::deserialize(&mut Cursor::new(&e), true)?;
-                assert_eq!(sig, deser);
+                assert_eq!(&e[..], sig.to_compressed().as_ref());
+                assert_eq!(G::from_compressed(&e[..]), Ok(sig));
             }
         }
     }
@@ -131,9 +118,9 @@ where
 }
 
 /// Test sign functionality for Pop
-pub fn test_pop<G>(tests: Vec<TestVector>, len: usize) -> Result<()>
+pub fn test_pop<G>(tests: Vec<TestVector>) -> Result<()>
 where
-    G: BLSSignaturePop<ExpandMsgXmd<Sha256>> + CurveProjective + SerDes,
+    G: BLSSignaturePop<ExpandMsgXmd<Sha256>> + CurveProjective,
 {
     for TestVector { sk, expect, .. } in tests {
         let (_, pk) = G::keygen(&sk[..]);
@@ -142,17 +129,119 @@ where
         match expect {
             None => println!("{:?}", sig),
             Some(e) => {
-                let mut buf = [0u8; 96];
-                {
-                    let mut cur = Cursor::new(&mut buf[..]);
-                    sig.serialize(&mut cur, true)?;
-                }
-                assert_eq!(e.as_ref() as &[u8], &buf[..len]);
-
-                let deser = G::deserialize(&mut Cursor::new(&e), true)?;
-                assert_eq!(sig, deser);
+                assert_eq!(&e[..], sig.to_compressed().as_ref());
+                assert_eq!(G::from_compressed(&e[..]), Ok(sig));
             }
         }
     }
     Ok(())
 }
+
+/// The error that decoding should report for a file of invalid signatures, given its name
+pub fn sig_invalid_reason(name: &str) -> Result<BlsError> {
+    match name {
+        "not_in_subgroup" => Ok(BlsError::NotInSubgroup),
+        "compression_bit_unset"
+        | "infinity_not_zero"
+        | "infinity_sign_bit"
+        | "not_on_curve"
+        | "wrong_length"
+        | "x_not_canonical" => Ok(BlsError::InvalidEncoding),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unknown rejection reason {}", name),
+        )),
+    }
+}
+
+/// Test that invalid signature encodings are rejected for the reason given by the file name
+pub fn test_sig_invalid<G>(tests: Vec<TestVector>, name: &str) -> Result<()>
+where
+    G: BLSSignatureBasic<ExpandMsgXmd<Sha256>> + CurveProjective,
+{
+    let reason = sig_invalid_reason(name)?;
+    for TestVector { msg, sk, expect } in tests {
+        let e = expect.expect("invalid signature test vectors must include a signature");
+        assert_eq!(
+            Signature::<G, Basic>::from_bytes(&e).map(|_| ()),
+            Err(reason),
+            "{}",
+            name
+        );
+
+        // anything that decodes when skipping the checks must still fail to verify
+        let mut enc = <G::Affine as CurveAffine>::Compressed::empty();
+        if enc.as_ref().len() == e.len() {
+            enc.as_mut().copy_from_slice(&e);
+            if let Ok(p) = enc.into_affine_unchecked() {
+                let (_, pk) = G::keygen(sk);
+                assert!(!G::verify(pk, p.into_projective(), &msg));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Test EIP-2333 master key derivation
+pub fn test_derive_master(tests: Vec<TestVector>) -> Result<()> {
+    for TestVector {
+        sk: seed, expect, ..
+    } in tests
+    {
+        let master = derive_master_sk(&seed).expect("EIP-2333 seeds must be at least 32 bytes");
+        match expect {
+            None => println!("{:?}", master),
+            Some(e) => assert_eq!(e, _fr_to_bytes(&master)?),
+        }
+    }
+    Ok(())
+}
+
+/// Test EIP-2333 child key derivation
+pub fn test_derive_child(tests: Vec<TestVector>) -> Result<()> {
+    for TestVector { msg, sk, expect } in tests {
+        assert_eq!(msg.len(), 4, "EIP-2333 child indices are 4 bytes");
+        let index = u32::from_be_bytes([msg[0], msg[1], msg[2], msg[3]]);
+        let mut repr = FrRepr::default();
+        repr.read_be(Cursor::new(&sk))?;
+        let parent = Fr::from_repr(repr).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let child = derive_child_sk(&parent, index);
+        match expect {
+            None => println!("{:?}", child),
+            Some(e) => assert_eq!(e, _fr_to_bytes(&child)?),
+        }
+    }
+    Ok(())
+}
+
+// I2OSP(x, 32)
+fn _fr_to_bytes(x: &Fr) -> Result<Vec<u8>> {
+    let mut ret = Vec::with_capacity(32);
+    x.into_repr().write_be(&mut ret)?;
+    Ok(ret)
+}
+
+/// The password of the EIP-2335 test keystores
+pub const EIP2335_PASSWORD: &str = "\u{1d531}\u{1d522}\u{1d530}\u{1d531}\u{1d52d}\u{1d51e}\u{1d530}\u{1d530}\u{1d534}\u{1d52c}\u{1d52f}\u{1d521}\u{1f511}";
+
+/// The secret key stored in the EIP-2335 test keystores
+pub const EIP2335_SECRET: [u8; 32] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0xd6, 0x68, 0x9c, 0x08, 0x5a, 0xe1, 0x65, 0x83, 0x1e, 0x93,
+    0x4f, 0xf7, 0x63, 0xae, 0x46, 0xa2, 0xa6, 0xc1, 0x72, 0xb3, 0xf1, 0xb6, 0x0a, 0x8c, 0xe2, 0x6f,
+];
+
+/// Test decrypting an EIP-2335 keystore, whose public key is in G1
+pub fn test_keystore(json: &str, password: &str, secret: &[u8]) -> Result<()> {
+    let to_io = |e: BlsError| Error::new(ErrorKind::InvalidData, e);
+    let ks = Keystore::from_json(json).map_err(to_io)?;
+    let sk: SecretKey<G2, Pop> = ks.decrypt(password).map_err(to_io)?;
+    assert_eq!(&sk.to_bytes()[..], secret);
+    assert_eq!(&sk.public_key().to_bytes()[..], ks.pubkey());
+    assert_eq!(
+        ks.decrypt::<G2, Pop, ExpandMsgXmd<Sha256>>("not the password")
+            .map(|_| ()),
+        Err(BlsError::InvalidPassword)
+    );
+    assert_eq!(Keystore::from_json(&ks.to_json()), Ok(ks));
+    Ok(())
+}
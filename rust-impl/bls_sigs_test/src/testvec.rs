@@ -1,7 +1,7 @@
 use std::env::{args, var};
-use std::fs::{read_dir, File};
-use std::io::{BufRead, BufReader, Error, ErrorKind, Result};
-use std::path::PathBuf;
+use std::fs::{read_dir, read_to_string, File};
+use std::io::{BufRead, BufReader, Error, Result};
+use std::path::{Path, PathBuf};
 
 fn hexnum(c: u8) -> u8 {
     match c {
@@ -15,7 +15,7 @@ fn hexnum(c: u8) -> u8 {
 // ASCII hexstring to bytes
 fn hexstring_to_bytes(input: &str) -> Vec<u8> {
     let input = input.as_bytes();
-    assert!(input.len() % 2 == 0);
+    assert!(input.len().is_multiple_of(2));
     let ret_len = input.len() / 2;
     let mut ret = Vec::<u8>::with_capacity(ret_len);
 
@@ -41,7 +41,7 @@ fn proc_testvec_line(input: &str) -> TestVector {
     let mut result: Vec<Vec<u8>> = input
         .split_ascii_whitespace()
         .take(3)
-        .map(|s| hexstring_to_bytes(s))
+        .map(hexstring_to_bytes)
         .collect();
     let expect = if result.len() > 2 { result.pop() } else { None };
     let sk = result.pop().unwrap();
@@ -80,8 +80,75 @@ pub fn get_dflt_vecs(test_type: &str) -> Result<Box<dyn Iterator<Item = Result<V
             proc_testvec_file(d.unwrap().path().to_str().unwrap())
         })))
     } else {
-        Err(Error::new(
-            ErrorKind::Other,
+        Err(Error::other(
+            "No cmdline arguments and std test vectors not found",
+        ))
+    }
+}
+
+// test vectors grouped by file, along with each file's name
+type NamedVecs = Box<dyn Iterator<Item = Result<(String, Vec<TestVector>)>>>;
+
+// Process a test vector file, keeping its name (e.g., the rejection reason for invalid signatures)
+fn proc_named_testvec_file(path: &Path) -> Result<(String, Vec<TestVector>)> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    Ok((name, proc_testvec_file(path.to_str().unwrap())?))
+}
+
+/// Like get_vecs, but also gives the name of the file each group of test vectors came from
+pub fn get_named_vecs(test_type: &str) -> Result<NamedVecs> {
+    if args().len() > 1 {
+        Ok(Box::new(
+            args().skip(1).map(|a| proc_named_testvec_file(a.as_ref())),
+        ))
+    } else {
+        get_dflt_named_vecs(test_type)
+    }
+}
+
+/// Like get_dflt_vecs, but also gives the name of the file each group of test vectors came from
+pub fn get_dflt_named_vecs(test_type: &str) -> Result<NamedVecs> {
+    if let Ok(dir) = var("CARGO_MANIFEST_DIR") {
+        let mut pbuf = PathBuf::from(dir);
+        pbuf.pop();
+        pbuf.pop();
+        pbuf.push("test-vectors");
+        pbuf.push(test_type);
+        Ok(Box::new(
+            read_dir(pbuf)?.map(|d| proc_named_testvec_file(&d?.path())),
+        ))
+    } else {
+        Err(Error::other(
+            "No cmdline arguments and std test vectors not found",
+        ))
+    }
+}
+
+/// Get the contents of all the specified files, or of the default files if none were specified.
+/// This is for test vectors that are whole documents, like keystores.
+pub fn get_files(test_type: &str) -> Result<Vec<String>> {
+    if args().len() > 1 {
+        args().skip(1).map(read_to_string).collect()
+    } else {
+        get_dflt_files(test_type)
+    }
+}
+
+/// Get the contents of the default files.
+pub fn get_dflt_files(test_type: &str) -> Result<Vec<String>> {
+    if let Ok(dir) = var("CARGO_MANIFEST_DIR") {
+        let mut pbuf = PathBuf::from(dir);
+        pbuf.pop();
+        pbuf.pop();
+        pbuf.push("test-vectors");
+        pbuf.push(test_type);
+        read_dir(pbuf)?.map(|d| read_to_string(d?.path())).collect()
+    } else {
+        Err(Error::other(
             "No cmdline arguments and std test vectors not found",
         ))
     }
@@ -0,0 +1,205 @@
+/*!
+dudect-style timing tests
+
+Following Reparaz, Balasch, and Verbauwhede, "Dude, is my code constant time?" (DATE 2017):
+each measurement runs the operation on a secret drawn either from a fixed class (one chosen
+value) or from a random class, with the class picked at random. Welch's t-test then compares
+the two timing distributions, both as measured and cropped at a series of upper percentiles
+to discard interrupts and other noise. A t statistic above `T_THRESHOLD` means the running
+time depends on the secret.
+
+These tests only observe this machine and this build; a pass is evidence, not proof.
+*/
+
+use bls_sigs_ref::BLSSigCore;
+use ff::Field;
+use pairing_plus::bls12_381::Fr;
+use pairing_plus::hash_to_field::ExpandMsgXmd;
+use pairing_plus::CurveProjective;
+use rand_core::{RngCore, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use sha2::Sha256;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// |t| above this means the timing is secret dependent (the threshold used by dudect)
+pub const T_THRESHOLD: f64 = 10.0;
+
+// measurements per batch; the first batch sets the cropping percentiles
+const BATCH: usize = 1000;
+
+// number of cropped t-tests, in addition to the uncropped one
+const N_CROPS: usize = 100;
+
+/// The result of a timing test
+#[derive(Clone, Copy, Debug)]
+pub struct TimingReport {
+    /// The number of measurements taken
+    pub samples: usize,
+    /// The largest |t| over the uncropped and cropped tests
+    pub max_t: f64,
+}
+
+impl TimingReport {
+    /// True if the measurements show secret-dependent timing
+    pub fn leaks(&self) -> bool {
+        self.max_t > T_THRESHOLD
+    }
+}
+
+// Welch's t-test, with means and variances accumulated online (Welford)
+#[derive(Clone, Copy, Default)]
+struct TTest {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl TTest {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        if self.n[0] < 2.0 || self.n[1] < 2.0 {
+            return 0.0;
+        }
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        let den = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        if den == 0.0 {
+            0.0
+        } else {
+            ((self.mean[0] - self.mean[1]) / den).abs()
+        }
+    }
+}
+
+/// Time `op` on `samples` inputs, each either `fixed` or a fresh value from `random`
+pub fn test_timing<T, R, F>(samples: usize, fixed: T, mut random: R, mut op: F) -> TimingReport
+where
+    T: Clone,
+    R: FnMut() -> T,
+    F: FnMut(T),
+{
+    let mut rng = _rng();
+    let mut uncropped = TTest::default();
+    let mut cropped = [TTest::default(); N_CROPS];
+    let mut thresholds = Vec::new();
+
+    let mut done = 0;
+    while done < samples {
+        let n = BATCH.min(samples - done);
+        // prepare inputs before measuring, so that generating them is not timed
+        let classes: Vec<usize> = (0..n).map(|_| (rng.next_u32() & 1) as usize).collect();
+        let inputs: Vec<T> = classes
+            .iter()
+            .map(|&c| if c == 0 { fixed.clone() } else { random() })
+            .collect();
+
+        let mut times = Vec::with_capacity(n);
+        for input in inputs {
+            let start = Instant::now();
+            op(input);
+            times.push(start.elapsed().as_nanos() as f64);
+        }
+
+        if thresholds.is_empty() {
+            let mut sorted = times.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            thresholds = (0..N_CROPS)
+                .map(|i| {
+                    let p = 1.0 - 0.5f64.powf(10.0 * (i + 1) as f64 / N_CROPS as f64);
+                    sorted[((sorted.len() - 1) as f64 * p) as usize]
+                })
+                .collect();
+        }
+        for (&class, &time) in classes.iter().zip(times.iter()) {
+            uncropped.push(class, time);
+            for (test, &threshold) in cropped.iter_mut().zip(thresholds.iter()) {
+                if time < threshold {
+                    test.push(class, time);
+                }
+            }
+        }
+        done += n;
+    }
+
+    let max_t = cropped.iter().map(TTest::t).fold(uncropped.t(), f64::max);
+    TimingReport { samples, max_t }
+}
+
+/// Time CoreSign with the secret scalar 1 against random secret scalars
+pub fn test_timing_core_sign<G>(samples: usize) -> TimingReport
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+{
+    let mut rng = _rng();
+    test_timing(
+        samples,
+        Fr::one(),
+        || Fr::random(&mut rng),
+        |x_prime| {
+            black_box(G::core_sign(x_prime, "this is the message", "timing test"));
+        },
+    )
+}
+
+/// Time KeyGen with an all-zero secret key against random secret keys
+pub fn test_timing_keygen<G>(samples: usize) -> TimingReport
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>>,
+{
+    let mut rng = _rng();
+    test_timing(
+        samples,
+        [0u8; 32],
+        || {
+            let mut sk = [0u8; 32];
+            rng.fill_bytes(&mut sk);
+            sk
+        },
+        |sk| {
+            black_box(G::keygen(sk));
+        },
+    )
+}
+
+/// Time the variable-time `CurveProjective::mul_assign` the same way, as a control:
+/// a working setup should report a leak here
+pub fn test_timing_control<G>(samples: usize) -> TimingReport
+where
+    G: CurveProjective<Scalar = Fr>,
+{
+    let mut rng = _rng();
+    let p = G::random(&mut rng);
+    test_timing(
+        samples,
+        Fr::one(),
+        || Fr::random(&mut rng),
+        |x| {
+            let mut tmp = p;
+            tmp.mul_assign(x);
+            black_box(tmp);
+        },
+    )
+}
+
+// a generator for classes and inputs, seeded from the clock and a counter so that the generators
+// for classes and inputs are distinct
+fn _rng() -> XorShiftRng {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let count = COUNT.fetch_add(1, Ordering::Relaxed) as u128;
+    let mut seed = [0u8; 16];
+    seed.copy_from_slice(&(nanos ^ (count << 96)).to_le_bytes());
+    seed[15] |= 1;
+    XorShiftRng::from_seed(seed)
+}
@@ -0,0 +1,225 @@
+#ifndef BLS_SIGS_REF_H
+#define BLS_SIGS_REF_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Length of a serialized secret key
+#define BLS_SECRET_KEY_LEN 32
+
+// Length of a compressed G1 point
+#define BLS_G1_LEN 48
+
+// Length of a compressed G2 point
+#define BLS_G2_LEN 96
+
+// Success, or a valid signature
+#define BLS_OK 0
+
+// See BlsError::InvalidEncoding
+#define BLS_ERR_INVALID_ENCODING 1
+
+// See BlsError::NotInSubgroup
+#define BLS_ERR_NOT_IN_SUBGROUP 2
+
+// See BlsError::IdentityKey
+#define BLS_ERR_IDENTITY_KEY 3
+
+// See BlsError::LengthMismatch
+#define BLS_ERR_LENGTH_MISMATCH 4
+
+// See BlsError::DuplicateMessage
+#define BLS_ERR_DUPLICATE_MESSAGE 5
+
+// See BlsError::DuplicateSigner
+#define BLS_ERR_DUPLICATE_SIGNER 6
+
+// See BlsError::MissingSigner
+#define BLS_ERR_MISSING_SIGNER 7
+
+// See BlsError::SignerOutOfRange
+#define BLS_ERR_SIGNER_OUT_OF_RANGE 8
+
+// See BlsError::EmptyInput
+#define BLS_ERR_EMPTY_INPUT 9
+
+// See BlsError::PairingFailure
+#define BLS_ERR_PAIRING_FAILURE 10
+
+// See BlsError::InvalidSignature
+#define BLS_ERR_INVALID_SIGNATURE 11
+
+// See BlsError::UnknownCiphersuite
+#define BLS_ERR_UNKNOWN_CIPHERSUITE 12
+
+// See BlsError::InvalidDst
+#define BLS_ERR_INVALID_DST 13
+
+// See BlsError::InvalidPath
+#define BLS_ERR_INVALID_PATH 14
+
+// See BlsError::InvalidSeed
+#define BLS_ERR_INVALID_SEED 15
+
+// See BlsError::InvalidKeystore
+#define BLS_ERR_INVALID_KEYSTORE 16
+
+// See BlsError::InvalidPassword
+#define BLS_ERR_INVALID_PASSWORD 17
+
+// See BlsError::InvalidThreshold
+#define BLS_ERR_INVALID_THRESHOLD 18
+
+// See BlsError::TooFewShares
+#define BLS_ERR_TOO_FEW_SHARES 19
+
+// See BlsError::InvalidDkgMessage
+#define BLS_ERR_INVALID_DKG_MESSAGE 20
+
+// See BlsError::InvalidShare
+#define BLS_ERR_INVALID_SHARE 21
+
+// See BlsError::TooFewDealers
+#define BLS_ERR_TOO_FEW_DEALERS 22
+
+// A required pointer argument was null
+#define BLS_ERR_NULL_POINTER -1
+
+// An internal error; panics are caught rather than unwinding into the caller
+#define BLS_ERR_INTERNAL -2
+
+// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G2_LEN` bytes)
+// from ikm, which must be at least 32 bytes
+int bls_basic_g1_keygen(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out, uint8_t *pk_out);
+
+// Sign msg, writing `BLS_G1_LEN` bytes to sig_out
+int bls_basic_g1_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+
+// Verify a signature on msg
+int bls_basic_g1_verify(const uint8_t *pk, const uint8_t *sig, const uint8_t *msg, size_t msg_len);
+
+// Aggregate n signatures, stored back to back in sigs
+int bls_basic_g1_aggregate(const uint8_t *sigs, size_t n, uint8_t *sig_out);
+
+// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+int bls_basic_g1_aggregate_verify(const uint8_t *pks,
+                                  const uint8_t *const *msgs,
+                                  const size_t *msg_lens,
+                                  size_t n,
+                                  const uint8_t *sig);
+
+// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G1_LEN` bytes)
+// from ikm, which must be at least 32 bytes
+int bls_basic_g2_keygen(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out, uint8_t *pk_out);
+
+// Sign msg, writing `BLS_G2_LEN` bytes to sig_out
+int bls_basic_g2_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+
+// Verify a signature on msg
+int bls_basic_g2_verify(const uint8_t *pk, const uint8_t *sig, const uint8_t *msg, size_t msg_len);
+
+// Aggregate n signatures, stored back to back in sigs
+int bls_basic_g2_aggregate(const uint8_t *sigs, size_t n, uint8_t *sig_out);
+
+// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+int bls_basic_g2_aggregate_verify(const uint8_t *pks,
+                                  const uint8_t *const *msgs,
+                                  const size_t *msg_lens,
+                                  size_t n,
+                                  const uint8_t *sig);
+
+// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G2_LEN` bytes)
+// from ikm, which must be at least 32 bytes
+int bls_aug_g1_keygen(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out, uint8_t *pk_out);
+
+// Sign msg, writing `BLS_G1_LEN` bytes to sig_out
+int bls_aug_g1_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+
+// Verify a signature on msg
+int bls_aug_g1_verify(const uint8_t *pk, const uint8_t *sig, const uint8_t *msg, size_t msg_len);
+
+// Aggregate n signatures, stored back to back in sigs
+int bls_aug_g1_aggregate(const uint8_t *sigs, size_t n, uint8_t *sig_out);
+
+// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+int bls_aug_g1_aggregate_verify(const uint8_t *pks,
+                                const uint8_t *const *msgs,
+                                const size_t *msg_lens,
+                                size_t n,
+                                const uint8_t *sig);
+
+// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G1_LEN` bytes)
+// from ikm, which must be at least 32 bytes
+int bls_aug_g2_keygen(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out, uint8_t *pk_out);
+
+// Sign msg, writing `BLS_G2_LEN` bytes to sig_out
+int bls_aug_g2_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+
+// Verify a signature on msg
+int bls_aug_g2_verify(const uint8_t *pk, const uint8_t *sig, const uint8_t *msg, size_t msg_len);
+
+// Aggregate n signatures, stored back to back in sigs
+int bls_aug_g2_aggregate(const uint8_t *sigs, size_t n, uint8_t *sig_out);
+
+// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+int bls_aug_g2_aggregate_verify(const uint8_t *pks,
+                                const uint8_t *const *msgs,
+                                const size_t *msg_lens,
+                                size_t n,
+                                const uint8_t *sig);
+
+// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G2_LEN` bytes)
+// from ikm, which must be at least 32 bytes
+int bls_pop_g1_keygen(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out, uint8_t *pk_out);
+
+// Sign msg, writing `BLS_G1_LEN` bytes to sig_out
+int bls_pop_g1_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+
+// Verify a signature on msg
+int bls_pop_g1_verify(const uint8_t *pk, const uint8_t *sig, const uint8_t *msg, size_t msg_len);
+
+// Aggregate n signatures, stored back to back in sigs
+int bls_pop_g1_aggregate(const uint8_t *sigs, size_t n, uint8_t *sig_out);
+
+// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+int bls_pop_g1_aggregate_verify(const uint8_t *pks,
+                                const uint8_t *const *msgs,
+                                const size_t *msg_lens,
+                                size_t n,
+                                const uint8_t *sig);
+
+// Prove possession of sk, writing `BLS_G1_LEN` bytes to pop_out
+int bls_pop_g1_pop_prove(const uint8_t *sk, uint8_t *pop_out);
+
+// Check a proof of possession for pk
+int bls_pop_g1_pop_verify(const uint8_t *pk, const uint8_t *pop);
+
+// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G1_LEN` bytes)
+// from ikm, which must be at least 32 bytes
+int bls_pop_g2_keygen(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out, uint8_t *pk_out);
+
+// Sign msg, writing `BLS_G2_LEN` bytes to sig_out
+int bls_pop_g2_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+
+// Verify a signature on msg
+int bls_pop_g2_verify(const uint8_t *pk, const uint8_t *sig, const uint8_t *msg, size_t msg_len);
+
+// Aggregate n signatures, stored back to back in sigs
+int bls_pop_g2_aggregate(const uint8_t *sigs, size_t n, uint8_t *sig_out);
+
+// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+int bls_pop_g2_aggregate_verify(const uint8_t *pks,
+                                const uint8_t *const *msgs,
+                                const size_t *msg_lens,
+                                size_t n,
+                                const uint8_t *sig);
+
+// Prove possession of sk, writing `BLS_G2_LEN` bytes to pop_out
+int bls_pop_g2_pop_prove(const uint8_t *sk, uint8_t *pop_out);
+
+// Check a proof of possession for pk
+int bls_pop_g2_pop_verify(const uint8_t *pk, const uint8_t *pop);
+
+#endif  /* BLS_SIGS_REF_H */
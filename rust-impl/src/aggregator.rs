@@ -0,0 +1,174 @@
+/*!
+Incremental aggregation of signatures and public keys
+*/
+
+use bitfield::Bitfield;
+use error::BlsError;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd};
+use pairing_plus::CurveProjective;
+use sha2::Sha256;
+use signature::{BLSSigCore, BLSSignaturePop};
+use std::fmt;
+use types::{PKType, Pop, PublicKey, Scheme, Signature};
+
+/// A running aggregate of signatures and public keys
+///
+/// Each contribution is tagged with the signer's index (e.g., its position in a committee),
+/// and the aggregator keeps track of which indices have contributed. Indices must be less than
+/// the size given to `new`, so that the record of contributors stays bounded. Adding an index
+/// twice, adding an index that is out of range, or removing an index that was never added, is
+/// an error and leaves the aggregate unchanged.
+///
+/// The aggregator does not check keys or signatures: callers should validate them before
+/// adding, e.g., with `PublicKey::try_key_validate` and `Signature::from_bytes`.
+pub struct Aggregator<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    sig: Signature<G, S, X>,
+    pk: PublicKey<G, S, X>,
+    participants: Bitfield,
+    size: usize,
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for Aggregator<G, S, X> {
+    fn clone(&self) -> Self {
+        Aggregator {
+            sig: self.sig,
+            pk: self.pk,
+            participants: self.participants.clone(),
+            size: self.size,
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for Aggregator<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Aggregator")
+            .field("sig", &self.sig)
+            .field("pk", &self.pk)
+            .field("participants", &self.participants)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> Aggregator<G, S, X> {
+    /// An empty aggregator for signers numbered below size
+    pub fn new(size: usize) -> Self {
+        Aggregator {
+            sig: Signature::new(G::zero()),
+            pk: PublicKey::new(PKType::<G, X>::zero()),
+            participants: Bitfield::new(),
+            size,
+        }
+    }
+
+    /// Add signer index's public key and signature to the aggregate
+    pub fn add(
+        &mut self,
+        index: usize,
+        pk: &PublicKey<G, S, X>,
+        sig: &Signature<G, S, X>,
+    ) -> Result<(), BlsError> {
+        if index >= self.size {
+            return Err(BlsError::SignerOutOfRange);
+        }
+        if self.participants.get(index) {
+            return Err(BlsError::DuplicateSigner);
+        }
+        self.participants.set(index);
+        self.sig = Signature::new(add_points(self.sig.point(), &sig.point()));
+        self.pk = PublicKey::new(add_points(self.pk.point(), &pk.point()));
+        Ok(())
+    }
+
+    /// Subtract signer index's public key and signature from the aggregate
+    pub fn remove(
+        &mut self,
+        index: usize,
+        pk: &PublicKey<G, S, X>,
+        sig: &Signature<G, S, X>,
+    ) -> Result<(), BlsError> {
+        if !self.participants.get(index) {
+            return Err(BlsError::MissingSigner);
+        }
+        self.participants.clear(index);
+        self.sig = Signature::new(sub_points(self.sig.point(), &sig.point()));
+        self.pk = PublicKey::new(sub_points(self.pk.point(), &pk.point()));
+        Ok(())
+    }
+
+    /// Add everything in other to this aggregate; their signers must be disjoint, and in range
+    /// for this aggregator
+    pub fn merge(&mut self, other: &Self) -> Result<(), BlsError> {
+        if other.participants.bit_len() > self.size {
+            return Err(BlsError::SignerOutOfRange);
+        }
+        if self.participants.intersects(&other.participants) {
+            return Err(BlsError::DuplicateSigner);
+        }
+        self.participants.union_with(&other.participants);
+        self.sig = Signature::new(add_points(self.sig.point(), &other.sig.point()));
+        self.pk = PublicKey::new(add_points(self.pk.point(), &other.pk.point()));
+        Ok(())
+    }
+
+    /// The number of signers in the aggregate
+    pub fn count(&self) -> usize {
+        self.participants.count()
+    }
+
+    /// The number of signer indices, as given to new
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Is signer index in the aggregate?
+    pub fn contains(&self, index: usize) -> bool {
+        self.participants.get(index)
+    }
+
+    /// The signers in the aggregate
+    pub fn participants(&self) -> &Bitfield {
+        &self.participants
+    }
+
+    /// The aggregate signature
+    pub fn signature(&self) -> Signature<G, S, X> {
+        self.sig
+    }
+
+    /// The sum of the signers' public keys
+    pub fn public_key(&self) -> PublicKey<G, S, X> {
+        self.pk
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> Aggregator<G, Pop, X> {
+    /// Verify the aggregate signature on msg under the aggregate public key
+    ///
+    /// Every key that was added must have had its proof of possession checked.
+    pub fn verify<B: AsRef<[u8]>>(&self, msg: B) -> bool {
+        self.try_verify(msg).is_ok()
+    }
+
+    /// like verify, but returns the reason for rejection
+    pub fn try_verify<B: AsRef<[u8]>>(&self, msg: B) -> Result<(), BlsError> {
+        if self.participants.is_empty() {
+            return Err(BlsError::EmptyInput);
+        }
+        self.sig.try_fast_aggregate_verify_apk(&self.pk, msg)
+    }
+}
+
+fn add_points<T: CurveProjective>(mut acc: T, p: &T) -> T {
+    acc.add_assign(p);
+    acc
+}
+
+fn sub_points<T: CurveProjective>(mut acc: T, p: &T) -> T {
+    acc.sub_assign(p);
+    acc
+}
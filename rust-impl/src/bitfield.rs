@@ -0,0 +1,117 @@
+/*!
+Participation bitfields
+*/
+
+use error::BlsError;
+
+/// A set of participant indices, stored as a bitfield
+///
+/// Participant `i` is bit `i % 8` of byte `i / 8`. The bitfield grows as needed, and trailing
+/// zero bytes are dropped, so two bitfields with the same participants compare equal.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitfield {
+    bytes: Vec<u8>,
+}
+
+impl Bitfield {
+    /// An empty bitfield
+    pub fn new() -> Self {
+        Bitfield { bytes: Vec::new() }
+    }
+
+    /// Is participant i in the set?
+    pub fn get(&self, i: usize) -> bool {
+        self.bytes
+            .get(i / 8)
+            .is_some_and(|b| b & (1 << (i % 8)) != 0)
+    }
+
+    /// Add participant i to the set
+    pub fn set(&mut self, i: usize) {
+        if self.bytes.len() <= i / 8 {
+            self.bytes.resize(i / 8 + 1, 0);
+        }
+        self.bytes[i / 8] |= 1 << (i % 8);
+    }
+
+    /// Remove participant i from the set
+    pub fn clear(&mut self, i: usize) {
+        if let Some(b) = self.bytes.get_mut(i / 8) {
+            *b &= !(1 << (i % 8));
+        }
+        self.trim();
+    }
+
+    /// The number of participants in the set
+    pub fn count(&self) -> usize {
+        self.bytes.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Is the set empty?
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// One more than the largest participant index in the set, or 0 if the set is empty
+    pub fn bit_len(&self) -> usize {
+        match self.bytes.last() {
+            None => 0,
+            Some(b) => self.bytes.len() * 8 - b.leading_zeros() as usize,
+        }
+    }
+
+    /// Encode the set as exactly `(len + 7) / 8` bytes, for participants numbered below len
+    pub fn to_bytes(&self, len: usize) -> Result<Vec<u8>, BlsError> {
+        if self.bit_len() > len {
+            return Err(BlsError::SignerOutOfRange);
+        }
+        let mut ret = self.bytes.clone();
+        ret.resize(len.div_ceil(8), 0);
+        Ok(ret)
+    }
+
+    /// Decode the output of to_bytes
+    ///
+    /// This rejects encodings of the wrong length and encodings with bits set at or above len,
+    /// so that every set of participants has exactly one encoding.
+    pub fn from_bytes(bytes: &[u8], len: usize) -> Result<Self, BlsError> {
+        if bytes.len() != len.div_ceil(8) {
+            return Err(BlsError::InvalidEncoding);
+        }
+        let mut ret = Bitfield {
+            bytes: bytes.to_vec(),
+        };
+        ret.trim();
+        if ret.bit_len() > len {
+            return Err(BlsError::InvalidEncoding);
+        }
+        Ok(ret)
+    }
+
+    /// Do self and other have any participants in common?
+    pub fn intersects(&self, other: &Bitfield) -> bool {
+        self.bytes.iter().zip(&other.bytes).any(|(a, b)| a & b != 0)
+    }
+
+    /// Add all of other's participants to the set
+    pub fn union_with(&mut self, other: &Bitfield) {
+        if self.bytes.len() < other.bytes.len() {
+            self.bytes.resize(other.bytes.len(), 0);
+        }
+        for (a, b) in self.bytes.iter_mut().zip(&other.bytes) {
+            *a |= b;
+        }
+    }
+
+    /// The participants in the set, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bytes.len() * 8).filter(move |&i| self.get(i))
+    }
+
+    // drop trailing zero bytes so that equality only depends on the participants
+    fn trim(&mut self) {
+        while self.bytes.last() == Some(&0) {
+            self.bytes.pop();
+        }
+    }
+}
@@ -0,0 +1,128 @@
+/*!
+Fixed committees of signers, with participation given by a bitfield
+*/
+
+use bitfield::Bitfield;
+use error::BlsError;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd};
+use pairing_plus::CurveProjective;
+use sha2::Sha256;
+use signature::{BLSSigCore, BLSSignaturePop};
+use std::fmt;
+use types::{Pop, PublicKey, Signature};
+
+/// An ordered list of public keys in the proof of possession scheme
+///
+/// Aggregate signatures from a committee name their signers with a bitfield over the
+/// committee's indices, rather than with a list of keys.
+pub struct Committee<G, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    pks: Vec<PublicKey<G, Pop, X>>,
+    // sum of all keys, used when most of the committee signed
+    apk: PublicKey<G, Pop, X>,
+}
+
+impl<G: BLSSigCore<X>, X: ExpandMsg> Clone for Committee<G, X> {
+    fn clone(&self) -> Self {
+        Committee {
+            pks: self.pks.clone(),
+            apk: self.apk,
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, X: ExpandMsg> fmt::Debug for Committee<G, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Committee").field("pks", &self.pks).finish()
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> Committee<G, X> {
+    /// Validate pks and build a committee from them, in order
+    ///
+    /// Every key's proof of possession must already have been checked.
+    pub fn new(pks: Vec<PublicKey<G, Pop, X>>) -> Result<Self, BlsError> {
+        let apk = PublicKey::aggregate(&pks[..])?;
+        Ok(Committee { pks, apk })
+    }
+
+    /// The number of members
+    pub fn len(&self) -> usize {
+        self.pks.len()
+    }
+
+    /// Does the committee have no members? (Always false: `new` rejects empty committees.)
+    pub fn is_empty(&self) -> bool {
+        self.pks.is_empty()
+    }
+
+    /// The members' public keys, in order
+    pub fn public_keys(&self) -> &[PublicKey<G, Pop, X>] {
+        &self.pks[..]
+    }
+
+    /// Encode a bitfield over this committee as `(len + 7) / 8` bytes
+    pub fn bitfield_to_bytes(&self, bitfield: &Bitfield) -> Result<Vec<u8>, BlsError> {
+        bitfield.to_bytes(self.len())
+    }
+
+    /// Decode a bitfield over this committee
+    pub fn bitfield_from_bytes(&self, bytes: &[u8]) -> Result<Bitfield, BlsError> {
+        Bitfield::from_bytes(bytes, self.len())
+    }
+
+    /// The sum of the public keys of the members named in bitfield
+    pub fn aggregate_public_key(
+        &self,
+        bitfield: &Bitfield,
+    ) -> Result<PublicKey<G, Pop, X>, BlsError> {
+        if bitfield.bit_len() > self.len() {
+            return Err(BlsError::SignerOutOfRange);
+        }
+        let count = bitfield.count();
+        if count == 0 {
+            return Err(BlsError::EmptyInput);
+        }
+        let mut apk;
+        if 2 * count > self.len() {
+            // fewer additions to subtract the non-signers from the sum of all keys
+            apk = self.apk.point();
+            for (i, pk) in self.pks.iter().enumerate() {
+                if !bitfield.get(i) {
+                    apk.sub_assign(&pk.point());
+                }
+            }
+        } else {
+            apk = CurveProjective::zero();
+            for i in bitfield.iter() {
+                apk.add_assign(&self.pks[i].point());
+            }
+        }
+        Ok(PublicKey::new(apk))
+    }
+
+    /// Verify an aggregate signature on msg by the members named in bitfield
+    pub fn verify_bitfield_aggregate<B: AsRef<[u8]>>(
+        &self,
+        bitfield: &Bitfield,
+        msg: B,
+        sig: &Signature<G, Pop, X>,
+    ) -> bool {
+        self.try_verify_bitfield_aggregate(bitfield, msg, sig)
+            .is_ok()
+    }
+
+    /// like verify_bitfield_aggregate, but returns the reason for rejection
+    pub fn try_verify_bitfield_aggregate<B: AsRef<[u8]>>(
+        &self,
+        bitfield: &Bitfield,
+        msg: B,
+        sig: &Signature<G, Pop, X>,
+    ) -> Result<(), BlsError> {
+        let apk = self.aggregate_public_key(bitfield)?;
+        sig.try_fast_aggregate_verify_apk(&apk, msg)
+    }
+}
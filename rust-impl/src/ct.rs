@@ -0,0 +1,156 @@
+/*!
+Constant-time scalar multiplication for secret scalars
+
+`CurveProjective::mul_assign` is a double-and-add loop that skips leading zero bits and adds only
+for set bits, so its running time depends on the scalar. Signing and key generation instead use
+`ct_mul_assign`, a fixed-window multiplication whose sequence of group operations and memory
+accesses does not depend on the scalar:
+
+- the scalar k is recoded as k' = k + r or k + 2r (r the group order), whichever has exactly
+  bitlen(r) + 1 bits, so every scalar has the same number of windows and a nonzero top window;
+- the table of small multiples is read in full for every window and the entry is picked with masks;
+- every window costs WINDOW doublings and one addition, whose result is discarded with a mask
+  when the window is zero.
+
+The underlying field arithmetic and the exceptional cases of the group law (adding the identity,
+or adding a point to itself) still branch. The accumulator is never the identity, and it meets
+the table entry only for a negligible fraction of scalars, so the group-law branches are not
+reached in practice; the field arithmetic is outside the scope of this module. The timing harness
+in `bls_sigs_test` measures what is left.
+*/
+
+use ff::{PrimeField, PrimeFieldRepr};
+use pairing_plus::bls12_381::{Fq, Fq2, G1, G2};
+use pairing_plus::CurveProjective;
+use std::hint::black_box;
+use std::mem::{align_of, size_of};
+use zeroize::Zeroize;
+
+/// Window width in bits
+const WINDOW: usize = 4;
+
+/// A group whose points can be chosen between without branching
+///
+/// This is implemented only for G1 and G2, whose points are known to be three coordinates over Fq
+/// or Fq2, i.e., plain arrays of u64 limbs. It is not exported, so no other group can implement
+/// it.
+pub trait CtSelect: CurveProjective {
+    /// self = src if choice == 1; self unchanged if choice == 0
+    fn ct_select(&mut self, src: &Self, choice: u64);
+}
+
+macro_rules! ct_select_impl {
+    ($group:ident, $base:ident) => {
+        impl CtSelect for $group {
+            fn ct_select(&mut self, src: &$group, choice: u64) {
+                const LEN: usize = 3 * size_of::<$base>() / size_of::<u64>();
+                // a point is exactly its coordinates' limbs, with no padding
+                const _: () = assert!(size_of::<$group>() == LEN * size_of::<u64>());
+                const _: () = assert!(align_of::<$group>() == align_of::<u64>());
+                // safety: the assertions above hold, and every limb of the result is either
+                //         self's or src's, so the result is one of the two points
+                let (dst, src) = unsafe {
+                    (
+                        &mut *(self as *mut $group as *mut [u64; LEN]),
+                        &*(src as *const $group as *const [u64; LEN]),
+                    )
+                };
+                _select_limbs(dst, src, choice);
+            }
+        }
+    };
+}
+
+ct_select_impl!(G1, Fq);
+ct_select_impl!(G2, Fq2);
+
+/// Multiply p by the secret scalar x, in time independent of x
+pub(crate) fn ct_mul_assign<G: CtSelect>(p: &mut G, x: &G::Scalar) {
+    let mut k = _recode(x);
+    let nbits = <G::Scalar as PrimeField>::char().num_bits() as usize + 1;
+    let nwindows = nbits.div_ceil(WINDOW);
+
+    // table[i] = i * p for i in 1..2^WINDOW; table[0] is a dummy entry for zero windows
+    let mut table = [*p; 1 << WINDOW];
+    for i in 2..table.len() {
+        table[i] = table[i - 1];
+        table[i].add_assign(p);
+    }
+
+    let mut acc = _lookup(&table, _window(&k, nwindows - 1));
+    for w in (0..(nwindows - 1)).rev() {
+        for _ in 0..WINDOW {
+            acc.double();
+        }
+        let digit = _window(&k, w);
+        let mut sum = acc;
+        sum.add_assign(&_lookup(&table, digit));
+        acc.ct_select(&sum, _is_nonzero(digit));
+    }
+    k.zeroize();
+    *p = acc;
+}
+
+// k + r if that has exactly bitlen(r) + 1 bits, else k + 2r; either is congruent to k mod r
+fn _recode<F: PrimeField>(x: &F) -> Vec<u64> {
+    let mut repr = x.into_repr();
+    let r = F::char();
+    let top = r.num_bits() as usize;
+    let nlimbs = top / 64 + 1;
+
+    let mut k1 = vec![0u64; nlimbs];
+    k1[..repr.as_ref().len()].copy_from_slice(repr.as_ref());
+    repr.zeroize();
+    _add_assign(&mut k1, r.as_ref());
+    let mut k2 = k1.clone();
+    _add_assign(&mut k2, r.as_ref());
+
+    // k + r < 2^top implies k + 2r < 2^top + r < 2^(top + 1)
+    let has_top_bit = (k1[top / 64] >> (top % 64)) & 1;
+    for (a, b) in k1.iter_mut().zip(k2.iter()) {
+        let mask = black_box(has_top_bit.wrapping_sub(1));
+        *a ^= mask & (*a ^ *b);
+    }
+    k2.zeroize();
+    k1
+}
+
+// a += b, limbs little-endian; the carry out of the top limb is dropped
+fn _add_assign(a: &mut [u64], b: &[u64]) {
+    let mut carry = 0u64;
+    for (i, ai) in a.iter_mut().enumerate() {
+        let bi = b.get(i).cloned().unwrap_or(0);
+        let tmp = u128::from(*ai) + u128::from(bi) + u128::from(carry);
+        *ai = tmp as u64;
+        carry = (tmp >> 64) as u64;
+    }
+}
+
+// the w'th WINDOW-bit digit of k
+fn _window(k: &[u64], w: usize) -> u64 {
+    let bit = w * WINDOW;
+    // WINDOW divides 64, so a digit never straddles two limbs
+    (k.get(bit / 64).cloned().unwrap_or(0) >> (bit % 64)) & ((1 << WINDOW) - 1)
+}
+
+// 1 if x != 0, else 0, without branching
+fn _is_nonzero(x: u64) -> u64 {
+    (x | x.wrapping_neg()) >> 63
+}
+
+// table[idx], reading every entry
+fn _lookup<G: CtSelect>(table: &[G], idx: u64) -> G {
+    let mut ret = table[0];
+    for (i, entry) in table.iter().enumerate().skip(1) {
+        ret.ct_select(entry, 1 ^ _is_nonzero(i as u64 ^ idx));
+    }
+    ret
+}
+
+// dst = src if choice == 1; dst unchanged if choice == 0
+fn _select_limbs(dst: &mut [u64], src: &[u64], choice: u64) {
+    let mask = black_box(choice.wrapping_neg());
+    for (di, si) in dst.iter_mut().zip(src.iter()) {
+        *di ^= mask & (*di ^ *si);
+    }
+}
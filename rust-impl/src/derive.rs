@@ -0,0 +1,106 @@
+/*!
+Hierarchical key derivation (EIP-2333) and derivation paths (EIP-2334)
+
+A master secret key is derived from a seed of at least 32 bytes, and each child secret key from
+its parent and a 32-bit index. The parent-to-child step goes through a Lamport public key, so
+that a child key reveals nothing about its parent or siblings. Paths such as `m/12381/3600/0/0/0`
+name a sequence of child indices starting from the master key.
+*/
+
+use error::BlsError;
+use ff::{PrimeField, PrimeFieldRepr};
+use hkdf::Hkdf;
+use pairing_plus::bls12_381::Fr;
+use sha2::{Digest, Sha256};
+use signature::{_wipe_scalar, xprime_from_ikm};
+use zeroize::Zeroize;
+
+/// Number of 32-byte chunks in a Lamport secret key
+const LAMPORT_CHUNKS: usize = 255;
+
+/// Length of each chunk, the output length of SHA-256
+const LAMPORT_CHUNK_LEN: usize = 32;
+
+/// derive_master_SK(seed): the master secret scalar for a seed.
+/// Returns None if seed is shorter than 32 bytes.
+pub fn derive_master_sk<B: AsRef<[u8]>>(seed: B) -> Option<Fr> {
+    // HKDF_mod_r with empty key_info, which is the current draft's KeyGen
+    xprime_from_ikm(seed, b"")
+}
+
+/// derive_child_SK(parent_SK, index): the secret scalar of the index'th child of parent
+pub fn derive_child_sk(parent: &Fr, index: u32) -> Fr {
+    let mut compressed_lamport_pk = _parent_sk_to_lamport_pk(parent, index);
+    let child = xprime_from_ikm(&compressed_lamport_pk[..], b"")
+        .expect("the compressed Lamport public key is 32 bytes long");
+    compressed_lamport_pk.zeroize();
+    child
+}
+
+/// Parse an EIP-2334 path like `m/12381/3600/0/0/0` into its child indices
+pub fn parse_path(path: &str) -> Result<Vec<u32>, BlsError> {
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+        return Err(BlsError::InvalidPath);
+    }
+    components
+        .map(|c| {
+            // only plain decimal digits: no sign, whitespace, or hardened-index suffix
+            if c.is_empty() || !c.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(BlsError::InvalidPath);
+            }
+            c.parse::<u32>().map_err(|_| BlsError::InvalidPath)
+        })
+        .collect()
+}
+
+/// The secret scalar at path, starting from the master key for seed
+pub fn derive_path<B: AsRef<[u8]>>(seed: B, path: &str) -> Result<Fr, BlsError> {
+    let indices = parse_path(path)?;
+    let mut sk = derive_master_sk(seed).ok_or(BlsError::InvalidSeed)?;
+    for index in indices {
+        let child = derive_child_sk(&sk, index);
+        _wipe_scalar(&mut sk);
+        sk = child;
+    }
+    Ok(sk)
+}
+
+// parent_SK_to_lamport_PK: hash both Lamport secret keys chunk by chunk, then hash the result
+fn _parent_sk_to_lamport_pk(parent: &Fr, index: u32) -> Vec<u8> {
+    let salt = index.to_be_bytes();
+    let mut ikm = _i2osp_32(parent);
+    let mut lamport_pk = Sha256::new();
+    for _ in 0..2 {
+        let mut lamport_sk = _ikm_to_lamport_sk(&ikm[..], &salt[..]);
+        for chunk in lamport_sk.chunks(LAMPORT_CHUNK_LEN) {
+            lamport_pk.input(Sha256::digest(chunk));
+        }
+        lamport_sk.zeroize();
+        // the second Lamport key comes from the bitwise complement of the parent key
+        for b in ikm.iter_mut() {
+            *b = !*b;
+        }
+    }
+    ikm.zeroize();
+    lamport_pk.result().to_vec()
+}
+
+// IKM_to_lamport_SK: 255 chunks of HKDF output, concatenated
+fn _ikm_to_lamport_sk(ikm: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut okm = vec![0u8; LAMPORT_CHUNKS * LAMPORT_CHUNK_LEN];
+    assert!(Hkdf::<Sha256>::new(Some(salt), ikm)
+        .expand(&[], &mut okm[..])
+        .is_ok());
+    okm
+}
+
+// I2OSP(sk, 32)
+fn _i2osp_32(sk: &Fr) -> Vec<u8> {
+    let mut repr = sk.into_repr();
+    let mut ret = Vec::with_capacity(32);
+    repr.write_be(&mut ret)
+        .expect("writing to a Vec cannot fail");
+    repr.zeroize();
+    ret
+}
@@ -0,0 +1,545 @@
+/*!
+Distributed key generation (Pedersen's DKG, i.e., joint Feldman VSS)
+
+Each of n participants acts as a dealer for its own random polynomial of degree t - 1: it
+broadcasts commitments g^{a_k} to the coefficients, in the public-key group, and sends participant
+j the share f(j) as an opaque payload, encrypted by a caller-supplied function. Participants check
+each share against its dealer's commitment and broadcast a complaint about every share that is
+missing or wrong; an accused dealer answers each complaint by broadcasting the share in the clear.
+A dealer is qualified if it committed and answered every complaint against it with a valid share.
+
+The group secret key is the sum of the qualified dealers' secrets, which no one learns; each
+participant's share of it is the sum of the shares it received from qualified dealers, and the
+public keys of the group and of every share follow from the commitments. The result plugs into
+`ThresholdPublicKey` and `SecretKeyShare`, just as if a trusted dealer had split the key.
+
+This module only keeps state. Sending messages is up to the caller, who must broadcast
+commitments, complaints, and justifications reliably (every participant sees the same ones)
+and encrypt each share payload to its recipient. A participant runs the protocol as:
+
+1. `deal`, then broadcast the commitment and send each share to its recipient;
+2. `receive_commitment` and `receive_share` for every other dealer;
+3. `complaints`, broadcast them, and `receive_complaint` for every other participant's;
+4. `justifications`, broadcast them, and `receive_justification` for every other dealer's;
+5. `finish`.
+*/
+
+use error::BlsError;
+use ff::Field;
+use pairing_plus::bls12_381::Fr;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd};
+use pairing_plus::CurveProjective;
+use rand_core::RngCore;
+use sha2::Sha256;
+use signature::{_wipe_scalar, BLSSigCore};
+use std::collections::BTreeSet;
+use std::fmt;
+use threshold::{_index_to_fr, SecretKeyShare, ThresholdPublicKey};
+use types::{PKType, PublicKey, Scheme, SecretKey};
+use zeroize::Zeroize;
+
+/// The result of distributed key generation: the public side and this participant's share
+pub type DkgOutput<G, S, X> = (ThresholdPublicKey<G, S, X>, SecretKeyShare<G, S, X>);
+
+/// One participant's state in distributed key generation
+pub struct Dkg<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    index: u32,
+    threshold: usize,
+    // this participant's polynomial, lowest degree first
+    coeffs: Vec<Fr>,
+    // indexed by dealer - 1
+    commitments: Vec<Option<Vec<PublicKey<G, S, X>>>>,
+    shares: Vec<Option<SecretKey<G, S, X>>>,
+    // (dealer, accuser) pairs not yet answered with a valid share
+    complaints: BTreeSet<(u32, u32)>,
+    disqualified: BTreeSet<u32>,
+}
+
+/// A dealer's commitment to its polynomial, to be broadcast
+pub struct Commitment<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    dealer: u32,
+    coefficients: Vec<PublicKey<G, S, X>>,
+}
+
+/// A dealer's share for one recipient, as encrypted by the dealer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedShare {
+    dealer: u32,
+    recipient: u32,
+    payload: Vec<u8>,
+}
+
+/// An accusation that a dealer's share for the accuser was missing or wrong, to be broadcast
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Complaint {
+    dealer: u32,
+    accuser: u32,
+}
+
+/// A dealer's answer to a complaint: the accuser's share in the clear, to be broadcast
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Justification {
+    dealer: u32,
+    accuser: u32,
+    share: Vec<u8>,
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Drop for Dkg<G, S, X> {
+    fn drop(&mut self) {
+        // the received shares are SecretKeys, which wipe themselves
+        for c in self.coeffs.iter_mut() {
+            _wipe_scalar(c);
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for Dkg<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // never print the polynomial or the shares
+        f.debug_struct("Dkg")
+            .field("index", &self.index)
+            .field("threshold", &self.threshold)
+            .field("participants", &self.commitments.len())
+            .field("complaints", &self.complaints)
+            .field("disqualified", &self.disqualified)
+            .finish()
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for Commitment<G, S, X> {
+    fn clone(&self) -> Self {
+        Commitment {
+            dealer: self.dealer,
+            coefficients: self.coefficients.clone(),
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> PartialEq for Commitment<G, S, X> {
+    fn eq(&self, other: &Self) -> bool {
+        self.dealer == other.dealer && self.coefficients == other.coefficients
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Eq for Commitment<G, S, X> {}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for Commitment<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Commitment")
+            .field("dealer", &self.dealer)
+            .field("coefficients", &self.coefficients)
+            .finish()
+    }
+}
+
+impl<G, S, X> Dkg<G, S, X>
+where
+    G: BLSSigCore<X> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, X>,
+    X: ExpandMsg,
+{
+    /// Start distributed key generation as participant index (1 through n) of n,
+    /// for keys that any threshold participants can sign with
+    ///
+    /// This fails with InvalidThreshold unless 1 <= threshold <= n < 2^32, and with
+    /// SignerOutOfRange unless 1 <= index <= n.
+    pub fn new<R: RngCore>(
+        index: u32,
+        threshold: usize,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Self, BlsError> {
+        if threshold == 0 || threshold > n || n > u32::MAX as usize {
+            return Err(BlsError::InvalidThreshold);
+        }
+        if index == 0 || index as usize > n {
+            return Err(BlsError::SignerOutOfRange);
+        }
+        Ok(Dkg {
+            index,
+            threshold,
+            coeffs: (0..threshold).map(|_| Fr::random(rng)).collect(),
+            commitments: vec![None; n],
+            shares: (0..n).map(|_| None).collect(),
+            complaints: BTreeSet::new(),
+            disqualified: BTreeSet::new(),
+        })
+    }
+
+    /// This participant's index
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The number of participants
+    pub fn len(&self) -> usize {
+        self.commitments.len()
+    }
+
+    /// Always false: there is at least one participant
+    pub fn is_empty(&self) -> bool {
+        self.commitments.is_empty()
+    }
+
+    /// The commitment to this participant's polynomial, and its share for every other
+    /// participant, encrypted with encrypt(recipient, plaintext)
+    pub fn deal<F>(&mut self, mut encrypt: F) -> (Commitment<G, S, X>, Vec<EncryptedShare>)
+    where
+        F: FnMut(u32, &[u8]) -> Vec<u8>,
+    {
+        let coefficients: Vec<PublicKey<G, S, X>> = self
+            .coeffs
+            .iter()
+            .map(|&c| SecretKey::<G, S, X>::new(c).public_key())
+            .collect();
+        let mut shares = Vec::with_capacity(self.len() - 1);
+        for recipient in 1..=(self.len() as u32) {
+            let share = self._eval(recipient);
+            if recipient == self.index {
+                self.shares[recipient as usize - 1] = Some(share);
+            } else {
+                let mut plaintext = share.to_bytes();
+                shares.push(EncryptedShare {
+                    dealer: self.index,
+                    recipient,
+                    payload: encrypt(recipient, &plaintext[..]),
+                });
+                plaintext.zeroize();
+            }
+        }
+        self.commitments[self.index as usize - 1] = Some(coefficients.clone());
+        (
+            Commitment {
+                dealer: self.index,
+                coefficients,
+            },
+            shares,
+        )
+    }
+
+    /// Record another dealer's commitment
+    ///
+    /// This fails with InvalidDkgMessage if the commitment has the wrong number of coefficients
+    /// or differs from one already received from the same dealer.
+    pub fn receive_commitment(&mut self, commitment: &Commitment<G, S, X>) -> Result<(), BlsError> {
+        let slot = self._dealer_slot(commitment.dealer)?;
+        if commitment.coefficients.len() != self.threshold {
+            return Err(BlsError::InvalidDkgMessage);
+        }
+        match self.commitments[slot] {
+            Some(ref c) if c != &commitment.coefficients => Err(BlsError::InvalidDkgMessage),
+            Some(_) => Ok(()),
+            None => {
+                self.commitments[slot] = Some(commitment.coefficients.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Decrypt another dealer's share for this participant with decrypt(dealer, ciphertext),
+    /// and check it against the dealer's commitment, which must already have been received
+    ///
+    /// A share that fails to decrypt or does not match the commitment is rejected with
+    /// InvalidShare; `complaints` then includes a complaint about it.
+    pub fn receive_share<F>(
+        &mut self,
+        share: &EncryptedShare,
+        mut decrypt: F,
+    ) -> Result<(), BlsError>
+    where
+        F: FnMut(u32, &[u8]) -> Option<Vec<u8>>,
+    {
+        let slot = self._dealer_slot(share.dealer)?;
+        if share.recipient != self.index || self.commitments[slot].is_none() {
+            return Err(BlsError::InvalidDkgMessage);
+        }
+        let received = decrypt(share.dealer, &share.payload[..]).and_then(|mut plaintext| {
+            let sk = SecretKey::from_bytes(&plaintext[..]).ok();
+            plaintext.zeroize();
+            sk
+        });
+        match received {
+            Some(sk) if self._check_share(share.dealer, self.index, &sk) => {
+                self.shares[slot] = Some(sk);
+                Ok(())
+            }
+            _ => Err(BlsError::InvalidShare),
+        }
+    }
+
+    /// Complaints about every committed dealer whose share for this participant is missing or
+    /// wrong, to be broadcast
+    pub fn complaints(&mut self) -> Vec<Complaint> {
+        let me = self.index;
+        let ret: Vec<Complaint> = (1..=(self.len() as u32))
+            .filter(|&d| {
+                let slot = d as usize - 1;
+                self.commitments[slot].is_some() && self.shares[slot].is_none()
+            })
+            .map(|dealer| Complaint {
+                dealer,
+                accuser: me,
+            })
+            .collect();
+        self.complaints
+            .extend(ret.iter().map(|c| (c.dealer, c.accuser)));
+        ret
+    }
+
+    /// Record another participant's complaint
+    pub fn receive_complaint(&mut self, complaint: &Complaint) -> Result<(), BlsError> {
+        self._dealer_slot(complaint.dealer)?;
+        self._dealer_slot(complaint.accuser)?;
+        if complaint.dealer == complaint.accuser {
+            return Err(BlsError::InvalidDkgMessage);
+        }
+        self.complaints
+            .insert((complaint.dealer, complaint.accuser));
+        Ok(())
+    }
+
+    /// Answers to every complaint against this participant, to be broadcast
+    ///
+    /// The answers are valid, so this also resolves the complaints.
+    pub fn justifications(&mut self) -> Vec<Justification> {
+        let me = self.index;
+        let ret: Vec<Justification> = self
+            .complaints
+            .iter()
+            .filter(|&&(dealer, _)| dealer == me)
+            .map(|&(dealer, accuser)| Justification {
+                dealer,
+                accuser,
+                share: self._eval(accuser).to_bytes().to_vec(),
+            })
+            .collect();
+        self.complaints.retain(|&(dealer, _)| dealer != me);
+        ret
+    }
+
+    /// Check another dealer's answer to a complaint
+    ///
+    /// A valid answer resolves the complaint, and gives the accuser its share if this is the
+    /// accuser. An answer that does not match the dealer's commitment disqualifies the dealer
+    /// and is rejected with InvalidShare.
+    pub fn receive_justification(&mut self, just: &Justification) -> Result<(), BlsError> {
+        let slot = self._dealer_slot(just.dealer)?;
+        if !self.complaints.contains(&(just.dealer, just.accuser)) {
+            return Err(BlsError::InvalidDkgMessage);
+        }
+        match SecretKey::from_bytes(&just.share[..]) {
+            Ok(sk) if self._check_share(just.dealer, just.accuser, &sk) => {
+                self.complaints.remove(&(just.dealer, just.accuser));
+                if just.accuser == self.index {
+                    self.shares[slot] = Some(sk);
+                }
+                Ok(())
+            }
+            _ => {
+                self.disqualified.insert(just.dealer);
+                Err(BlsError::InvalidShare)
+            }
+        }
+    }
+
+    /// The qualified dealers: those that committed, were not disqualified, and have answered
+    /// every complaint against them
+    pub fn qualified(&self) -> Vec<u32> {
+        (1..=(self.len() as u32))
+            .filter(|&d| {
+                self.commitments[d as usize - 1].is_some()
+                    && !self.disqualified.contains(&d)
+                    && !self.complaints.iter().any(|&(dealer, _)| dealer == d)
+            })
+            .collect()
+    }
+
+    /// The threshold public key, and this participant's share of the group secret key
+    ///
+    /// This fails with TooFewDealers if fewer than threshold dealers are qualified, and with
+    /// InvalidShare if this participant lacks a valid share from a qualified dealer because it
+    /// did not complain about it.
+    pub fn finish(&self) -> Result<DkgOutput<G, S, X>, BlsError> {
+        let qualified = self.qualified();
+        if qualified.len() < self.threshold {
+            return Err(BlsError::TooFewDealers);
+        }
+
+        // the commitment to the sum of the qualified polynomials, and this participant's
+        // evaluation of that sum
+        let mut coefficients = vec![PKType::<G, X>::zero(); self.threshold];
+        let mut x = Fr::zero();
+        for &dealer in &qualified {
+            let slot = dealer as usize - 1;
+            let commitment = self.commitments[slot]
+                .as_ref()
+                .expect("qualified dealers have committed");
+            for (acc, c) in coefficients.iter_mut().zip(commitment.iter()) {
+                acc.add_assign(&c.point());
+            }
+            match self.shares[slot] {
+                Some(ref sk) => {
+                    let mut share = sk.x_prime();
+                    x.add_assign(&share);
+                    _wipe_scalar(&mut share);
+                }
+                None => {
+                    _wipe_scalar(&mut x);
+                    return Err(BlsError::InvalidShare);
+                }
+            }
+        }
+        let sk = SecretKey::new(x);
+        _wipe_scalar(&mut x);
+
+        let group_key = PublicKey::new(coefficients[0]);
+        let shares = (1..=(self.len() as u32))
+            .map(|j| PublicKey::new(_eval_commitment(&coefficients[..], j)))
+            .collect();
+        Ok((
+            ThresholdPublicKey::from_parts(self.threshold, group_key, shares),
+            SecretKeyShare::from_parts(self.index, sk, group_key),
+        ))
+    }
+
+    // dealer - 1, if dealer is a participant
+    fn _dealer_slot(&self, dealer: u32) -> Result<usize, BlsError> {
+        if dealer == 0 || dealer as usize > self.len() {
+            Err(BlsError::SignerOutOfRange)
+        } else {
+            Ok(dealer as usize - 1)
+        }
+    }
+
+    // this participant's polynomial at index, by Horner's rule
+    fn _eval(&self, index: u32) -> SecretKey<G, S, X> {
+        let x = _index_to_fr(index);
+        let mut y = Fr::zero();
+        for c in self.coeffs.iter().rev() {
+            y.mul_assign(&x);
+            y.add_assign(c);
+        }
+        let ret = SecretKey::new(y);
+        _wipe_scalar(&mut y);
+        ret
+    }
+
+    // does share match dealer's commitment at index? (the commitment must be present)
+    fn _check_share(&self, dealer: u32, index: u32, share: &SecretKey<G, S, X>) -> bool {
+        match self.commitments[dealer as usize - 1] {
+            Some(ref commitment) => {
+                let points: Vec<PKType<G, X>> = commitment.iter().map(|c| c.point()).collect();
+                share.public_key().point() == _eval_commitment(&points[..], index)
+            }
+            None => false,
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> Commitment<G, S, X> {
+    /// A commitment received from dealer: g^{a_0}, ..., g^{a_(t-1)}
+    pub fn new(dealer: u32, coefficients: Vec<PublicKey<G, S, X>>) -> Self {
+        Commitment {
+            dealer,
+            coefficients,
+        }
+    }
+
+    /// The index of the dealer
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// The commitments to the coefficients, lowest degree first
+    pub fn coefficients(&self) -> &[PublicKey<G, S, X>] {
+        &self.coefficients[..]
+    }
+}
+
+impl EncryptedShare {
+    /// A share received from dealer for recipient
+    pub fn new(dealer: u32, recipient: u32, payload: Vec<u8>) -> Self {
+        EncryptedShare {
+            dealer,
+            recipient,
+            payload,
+        }
+    }
+
+    /// The index of the dealer
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// The index of the recipient
+    pub fn recipient(&self) -> u32 {
+        self.recipient
+    }
+
+    /// The encrypted share
+    pub fn payload(&self) -> &[u8] {
+        &self.payload[..]
+    }
+}
+
+impl Complaint {
+    /// A complaint by accuser about dealer
+    pub fn new(dealer: u32, accuser: u32) -> Self {
+        Complaint { dealer, accuser }
+    }
+
+    /// The index of the accused dealer
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// The index of the participant complaining
+    pub fn accuser(&self) -> u32 {
+        self.accuser
+    }
+}
+
+impl Justification {
+    /// An answer from dealer to accuser's complaint
+    pub fn new(dealer: u32, accuser: u32, share: Vec<u8>) -> Self {
+        Justification {
+            dealer,
+            accuser,
+            share,
+        }
+    }
+
+    /// The index of the accused dealer
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// The index of the participant that complained
+    pub fn accuser(&self) -> u32 {
+        self.accuser
+    }
+
+    /// The accuser's share, big-endian
+    pub fn share(&self) -> &[u8] {
+        &self.share[..]
+    }
+}
+
+// the committed polynomial at index, in the exponent: sum_k C_k^(index^k), by Horner's rule
+fn _eval_commitment<P: CurveProjective<Scalar = Fr>>(coefficients: &[P], index: u32) -> P {
+    let x = _index_to_fr(index);
+    let mut y = P::zero();
+    for c in coefficients.iter().rev() {
+        y.mul_assign(x);
+        y.add_assign(c);
+    }
+    y
+}
@@ -0,0 +1,275 @@
+/*!
+Schemes with application-defined domain separation tags
+
+By default, each scheme hashes messages under its ciphersuite ID (its `CSUITE`). Protocols that
+share keys but must not accept each other's signatures can instead build a `CustomScheme` with
+their own domain separation tag (DST). The custom scheme keeps the semantics of the underlying
+scheme: Basic still requires distinct messages in aggregates, Aug still prepends the public key
+to each message, and Pop still proves possession, under a separate PoP tag.
+*/
+
+use error::BlsError;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd, ExpandMsgXof};
+use sha2::digest::{BlockInput, Digest, ExtendableOutput, Input};
+use sha2::Sha256;
+use signature::{
+    _keys_validate, _pop_prove, _pop_verify, _wipe_scalar, BLSSigCore, BLSSignaturePop,
+};
+use std::fmt;
+use std::marker::PhantomData;
+use types::{PKType, Pop, PublicKey, Scheme, SchemeMarker, SecretKey, Signature};
+
+/// Longest DST that can be used directly; longer ones are hashed (see ExpandMsgDst)
+pub const MAX_DST_LEN: usize = 255;
+
+// prefix for hashing oversize DSTs, from the hash-to-curve draft
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// A message expander that can shorten DSTs longer than MAX_DST_LEN bytes
+pub trait ExpandMsgDst: ExpandMsg {
+    /// Hash an oversize DST, as specified in the hash-to-curve draft
+    fn oversize_dst(dst: &[u8]) -> Vec<u8>;
+}
+
+impl<HashT: Digest + BlockInput> ExpandMsgDst for ExpandMsgXmd<HashT> {
+    fn oversize_dst(dst: &[u8]) -> Vec<u8> {
+        HashT::new()
+            .chain(OVERSIZE_DST_PREFIX)
+            .chain(dst)
+            .result()
+            .to_vec()
+    }
+}
+
+impl<HashT: Default + ExtendableOutput + Input> ExpandMsgDst for ExpandMsgXof<HashT> {
+    fn oversize_dst(dst: &[u8]) -> Vec<u8> {
+        // ceil(2 * k / 8) bytes for k = 128 bits of security
+        HashT::default()
+            .chain(OVERSIZE_DST_PREFIX)
+            .chain(dst)
+            .vec_result(32)
+    }
+}
+
+// reject empty DSTs, and hash oversize ones
+fn _prepare_dst<X: ExpandMsgDst>(dst: Option<Vec<u8>>) -> Result<Vec<u8>, BlsError> {
+    match dst {
+        None => Err(BlsError::InvalidDst),
+        Some(ref dst) if dst.is_empty() => Err(BlsError::InvalidDst),
+        Some(ref dst) if dst.len() > MAX_DST_LEN => Ok(X::oversize_dst(dst)),
+        Some(dst) => Ok(dst),
+    }
+}
+
+/// Builder for a CustomScheme
+pub struct SchemeBuilder<G, S, X = ExpandMsgXmd<Sha256>> {
+    dst: Option<Vec<u8>>,
+    pop_dst: Option<Vec<u8>>,
+    _scheme: SchemeMarker<G, S, X>,
+}
+
+/// Scheme S with signatures in G, under an application-defined DST
+pub struct CustomScheme<G, S, X = ExpandMsgXmd<Sha256>> {
+    dst: Vec<u8>,
+    // empty unless S is Pop
+    pop_dst: Vec<u8>,
+    _scheme: SchemeMarker<G, S, X>,
+}
+
+impl<G, S, X> Clone for SchemeBuilder<G, S, X> {
+    fn clone(&self) -> Self {
+        SchemeBuilder {
+            dst: self.dst.clone(),
+            pop_dst: self.pop_dst.clone(),
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<G, S, X> fmt::Debug for SchemeBuilder<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SchemeBuilder")
+            .field("dst", &self.dst)
+            .field("pop_dst", &self.pop_dst)
+            .finish()
+    }
+}
+
+impl<G, S, X> Clone for CustomScheme<G, S, X> {
+    fn clone(&self) -> Self {
+        CustomScheme {
+            dst: self.dst.clone(),
+            pop_dst: self.pop_dst.clone(),
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<G, S, X> fmt::Debug for CustomScheme<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomScheme")
+            .field("dst", &String::from_utf8_lossy(&self.dst[..]))
+            .field("pop_dst", &String::from_utf8_lossy(&self.pop_dst[..]))
+            .finish()
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsgDst> Default for SchemeBuilder<G, S, X> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsgDst> SchemeBuilder<G, S, X> {
+    /// A builder with no DST set
+    pub fn new() -> Self {
+        SchemeBuilder {
+            dst: None,
+            pop_dst: None,
+            _scheme: PhantomData,
+        }
+    }
+
+    /// Set the DST for signing and verification
+    pub fn dst<B: AsRef<[u8]>>(mut self, dst: B) -> Self {
+        self.dst = Some(dst.as_ref().to_vec());
+        self
+    }
+
+    /// Build the scheme
+    ///
+    /// This fails if the DST is missing or empty, or, for Pop, if the PoP DST is missing,
+    /// empty, or the same as the signing DST. DSTs longer than MAX_DST_LEN bytes are hashed.
+    pub fn build(self) -> Result<CustomScheme<G, S, X>, BlsError> {
+        let dst = _prepare_dst::<X>(self.dst)?;
+        let pop_dst = if S::POP {
+            let pop_dst = _prepare_dst::<X>(self.pop_dst)?;
+            if pop_dst == dst {
+                return Err(BlsError::InvalidDst);
+            }
+            pop_dst
+        } else {
+            Vec::new()
+        };
+        Ok(CustomScheme {
+            dst,
+            pop_dst,
+            _scheme: PhantomData,
+        })
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsgDst> SchemeBuilder<G, Pop, X> {
+    /// Set the DST for proofs of possession, which must differ from the signing DST
+    pub fn pop_dst<B: AsRef<[u8]>>(mut self, dst: B) -> Self {
+        self.pop_dst = Some(dst.as_ref().to_vec());
+        self
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> CustomScheme<G, S, X> {
+    /// The DST for signing and verification, after hashing if it was oversize
+    pub fn dst(&self) -> &[u8] {
+        &self.dst[..]
+    }
+
+    /// Sign a message
+    pub fn sign<B: AsRef<[u8]>>(&self, sk: &SecretKey<G, S, X>, msg: B) -> Signature<G, S, X> {
+        Signature::new(S::sign_with_dst(sk.x_prime(), msg, &self.dst[..]))
+    }
+
+    /// Verify sig on msg under pk
+    pub fn verify<B: AsRef<[u8]>>(
+        &self,
+        pk: &PublicKey<G, S, X>,
+        msg: B,
+        sig: &Signature<G, S, X>,
+    ) -> bool {
+        self.try_verify(pk, msg, sig).is_ok()
+    }
+
+    /// like verify, but returns the reason for rejection
+    pub fn try_verify<B: AsRef<[u8]>>(
+        &self,
+        pk: &PublicKey<G, S, X>,
+        msg: B,
+        sig: &Signature<G, S, X>,
+    ) -> Result<(), BlsError> {
+        S::try_verify_with_dst(pk.point(), sig.point(), msg, &self.dst[..])
+    }
+
+    /// Verify an aggregated signature on msgs under pks
+    pub fn aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, S, X>],
+        msgs: &[B],
+        sig: &Signature<G, S, X>,
+    ) -> bool {
+        self.try_aggregate_verify(pks, msgs, sig).is_ok()
+    }
+
+    /// like aggregate_verify, but returns the reason for rejection
+    pub fn try_aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, S, X>],
+        msgs: &[B],
+        sig: &Signature<G, S, X>,
+    ) -> Result<(), BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point()).collect();
+        S::try_aggregate_verify_with_dst(&points[..], msgs, sig.point(), &self.dst[..])
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> CustomScheme<G, Pop, X> {
+    /// The DST for proofs of possession, after hashing if it was oversize
+    pub fn pop_dst(&self) -> &[u8] {
+        &self.pop_dst[..]
+    }
+
+    /// Prove possession of sk
+    pub fn pop_prove(&self, sk: &SecretKey<G, Pop, X>) -> Signature<G, Pop, X> {
+        let pk = sk.public_key().point();
+        let mut x_prime = sk.x_prime();
+        let proof = _pop_prove::<G, X>(&x_prime, &pk, &self.pop_dst[..]);
+        _wipe_scalar(&mut x_prime);
+        Signature::new(proof)
+    }
+
+    /// Check a proof of possession for pk
+    pub fn pop_verify(&self, pk: &PublicKey<G, Pop, X>, proof: &Signature<G, Pop, X>) -> bool {
+        self.try_pop_verify(pk, proof).is_ok()
+    }
+
+    /// like pop_verify, but returns the reason for rejection
+    pub fn try_pop_verify(
+        &self,
+        pk: &PublicKey<G, Pop, X>,
+        proof: &Signature<G, Pop, X>,
+    ) -> Result<(), BlsError> {
+        pk.try_key_validate()?;
+        _pop_verify::<G, X>(pk.point(), proof.point(), &self.pop_dst[..])
+    }
+
+    /// Verify a multisignature on msg under pks, whose PoPs must already have been checked
+    pub fn fast_aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: B,
+        sig: &Signature<G, Pop, X>,
+    ) -> bool {
+        self.try_fast_aggregate_verify(pks, msg, sig).is_ok()
+    }
+
+    /// like fast_aggregate_verify, but returns the reason for rejection
+    pub fn try_fast_aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: B,
+        sig: &Signature<G, Pop, X>,
+    ) -> Result<(), BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point()).collect();
+        _keys_validate::<G, X>(&points[..])?;
+        let apk = <G as BLSSignaturePop<X>>::try_aggregate_public_keys_prevalidated(&points[..])?;
+        <G as BLSSigCore<X>>::try_core_verify(apk, sig.point(), msg, &self.dst[..])
+    }
+}
@@ -0,0 +1,87 @@
+/*!
+Fixed-size encodings of points
+
+Points use the ZCash serialization of BLS12-381: the compressed form is the x-coordinate with
+flags in the three most significant bits, and the uncompressed form appends the y-coordinate.
+Decoding is checked: it rejects encodings that are not canonical, points that are not on the
+curve, and points outside the prime-order subgroup. It does not reject the identity, which is
+a valid encoding; KeyValidate rejects it as a public key.
+*/
+
+use error::BlsError;
+use pairing_plus::bls12_381::{G1, G2};
+use pairing_plus::{CurveAffine, CurveProjective, EncodedPoint};
+
+/// Compressed and uncompressed encodings as byte arrays of a fixed size per group
+pub trait PointEncoding: CurveProjective {
+    /// The compressed encoding, `[u8; 48]` in G1 and `[u8; 96]` in G2
+    type Compressed: AsRef<[u8]> + AsMut<[u8]> + Copy;
+
+    /// The uncompressed encoding, `[u8; 96]` in G1 and `[u8; 192]` in G2
+    type Uncompressed: AsRef<[u8]> + AsMut<[u8]> + Copy;
+
+    /// Encode this point in compressed form
+    fn to_compressed(&self) -> Self::Compressed;
+
+    /// Encode this point in uncompressed form
+    fn to_uncompressed(&self) -> Self::Uncompressed;
+
+    /// Decode a compressed point, with the checks described in the module documentation
+    fn from_compressed(bytes: &[u8]) -> Result<Self, BlsError>;
+
+    /// Decode an uncompressed point, with the checks described in the module documentation
+    fn from_uncompressed(bytes: &[u8]) -> Result<Self, BlsError>;
+}
+
+// decode with the pairing library, then insist that re-encoding gives back the same bytes
+fn _decode<T: CurveProjective, E: EncodedPoint<Affine = T::Affine>>(
+    bytes: &[u8],
+) -> Result<T, BlsError> {
+    let mut enc = E::empty();
+    if bytes.len() != enc.as_ref().len() {
+        return Err(BlsError::InvalidEncoding);
+    }
+    enc.as_mut().copy_from_slice(bytes);
+    let point = enc.into_affine()?;
+    // e.g., the point at infinity with the sign bit set decodes, but is not canonical
+    if E::from_affine(point).as_ref() != bytes {
+        return Err(BlsError::InvalidEncoding);
+    }
+    Ok(point.into_projective())
+}
+
+macro_rules! point_encoding {
+    ($group:ty, $compressed_len:expr, $uncompressed_len:expr) => {
+        impl PointEncoding for $group {
+            type Compressed = [u8; $compressed_len];
+            type Uncompressed = [u8; $uncompressed_len];
+
+            fn to_compressed(&self) -> [u8; $compressed_len] {
+                let mut ret = [0u8; $compressed_len];
+                ret.copy_from_slice(self.into_affine().into_compressed().as_ref());
+                ret
+            }
+
+            fn to_uncompressed(&self) -> [u8; $uncompressed_len] {
+                let mut ret = [0u8; $uncompressed_len];
+                ret.copy_from_slice(self.into_affine().into_uncompressed().as_ref());
+                ret
+            }
+
+            fn from_compressed(bytes: &[u8]) -> Result<Self, BlsError> {
+                _decode::<Self, <<Self as CurveProjective>::Affine as CurveAffine>::Compressed>(
+                    bytes,
+                )
+            }
+
+            fn from_uncompressed(bytes: &[u8]) -> Result<Self, BlsError> {
+                _decode::<Self, <<Self as CurveProjective>::Affine as CurveAffine>::Uncompressed>(
+                    bytes,
+                )
+            }
+        }
+    };
+}
+
+point_encoding!(G1, 48, 96);
+point_encoding!(G2, 96, 192);
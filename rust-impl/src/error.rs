@@ -0,0 +1,96 @@
+/*!
+Errors for BLS signatures
+*/
+
+use pairing_plus::GroupDecodingError;
+use std::error::Error;
+use std::fmt;
+
+/// The reason that a key, signature, or encoding was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlsError {
+    /// An encoded key or signature could not be decoded, or was not canonical
+    InvalidEncoding,
+    /// A point is not in the prime-order subgroup
+    NotInSubgroup,
+    /// A public key is the identity element
+    IdentityKey,
+    /// The numbers of public keys, messages, or signatures differ
+    LengthMismatch,
+    /// An aggregate signature covers the same message more than once
+    DuplicateMessage,
+    /// A signer was added to an aggregate that already includes it
+    DuplicateSigner,
+    /// A signer was removed from an aggregate that does not include it
+    MissingSigner,
+    /// A bitfield names a signer outside the committee
+    SignerOutOfRange,
+    /// There were no keys, messages, or signatures to work with
+    EmptyInput,
+    /// A domain separation tag is empty, or a PoP tag is missing or equal to the signing tag
+    InvalidDst,
+    /// No ciphersuite has the given ID
+    UnknownCiphersuite,
+    /// The final exponentiation of the pairing failed
+    PairingFailure,
+    /// The signature does not verify
+    InvalidSignature,
+    /// A key derivation path is not of the form m/i/j/...
+    InvalidPath,
+    /// A key derivation seed is shorter than 32 bytes
+    InvalidSeed,
+    /// A keystore is malformed or uses an unsupported KDF, cipher, or version
+    InvalidKeystore,
+    /// A keystore's checksum does not match, so the password is wrong
+    InvalidPassword,
+    /// A threshold is zero or exceeds the number of shares
+    InvalidThreshold,
+    /// Fewer partial signatures than the threshold were given
+    TooFewShares,
+    /// A key generation message is malformed, misaddressed, or out of order
+    InvalidDkgMessage,
+    /// A secret share does not match its dealer's commitment
+    InvalidShare,
+    /// Fewer dealers than the threshold are qualified
+    TooFewDealers,
+}
+
+impl fmt::Display for BlsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            BlsError::InvalidEncoding => "invalid encoding",
+            BlsError::NotInSubgroup => "point not in the prime-order subgroup",
+            BlsError::IdentityKey => "public key is the identity",
+            BlsError::LengthMismatch => "numbers of public keys, messages, or signatures differ",
+            BlsError::DuplicateMessage => "duplicate message",
+            BlsError::DuplicateSigner => "signer already aggregated",
+            BlsError::MissingSigner => "signer not aggregated",
+            BlsError::SignerOutOfRange => "signer index out of range",
+            BlsError::EmptyInput => "empty input",
+            BlsError::InvalidDst => "invalid domain separation tag",
+            BlsError::UnknownCiphersuite => "unknown ciphersuite",
+            BlsError::PairingFailure => "pairing computation failed",
+            BlsError::InvalidSignature => "invalid signature",
+            BlsError::InvalidPath => "invalid key derivation path",
+            BlsError::InvalidSeed => "key derivation seed too short",
+            BlsError::InvalidKeystore => "invalid keystore",
+            BlsError::InvalidPassword => "wrong keystore password",
+            BlsError::InvalidThreshold => "invalid threshold",
+            BlsError::TooFewShares => "fewer partial signatures than the threshold",
+            BlsError::InvalidDkgMessage => "invalid key generation message",
+            BlsError::InvalidShare => "share does not match commitment",
+            BlsError::TooFewDealers => "fewer qualified dealers than the threshold",
+        })
+    }
+}
+
+impl Error for BlsError {}
+
+impl From<GroupDecodingError> for BlsError {
+    fn from(e: GroupDecodingError) -> BlsError {
+        match e {
+            GroupDecodingError::NotInSubgroup => BlsError::NotInSubgroup,
+            _ => BlsError::InvalidEncoding,
+        }
+    }
+}
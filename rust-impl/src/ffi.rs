@@ -0,0 +1,655 @@
+/*!
+C interface
+
+Each function is named `bls_<scheme>_<group>_<operation>`, where the scheme is `basic`, `aug`,
+or `pop`, and the group is the one signatures live in: for `g1`, signatures are `BLS_G1_LEN`
+bytes and public keys are `BLS_G2_LEN` bytes, and vice versa for `g2`. Points are in compressed
+form, and secret keys are `BLS_SECRET_KEY_LEN`-byte big-endian integers. Key generation follows
+the draft's KeyGen with an empty key_info, so the input keying material must be at least 32
+bytes.
+
+Every function returns `BLS_OK` on success (for verification, that the signature is valid),
+and otherwise one of the `BLS_ERR_*` codes. Outputs are only written on success.
+
+# Safety
+
+Every pointer argument must be valid for the number of bytes implied by the function's
+documentation, and may only be null if the corresponding length is zero. Input and output
+buffers may not overlap.
+*/
+
+// the contract for pointer arguments is given once, in the module documentation
+#![allow(clippy::missing_safety_doc)]
+
+use error::BlsError;
+use pairing_plus::bls12_381::{G1, G2};
+use pairing_plus::hash_to_field::ExpandMsgXmd;
+use pairing_plus::{CurveAffine, CurveProjective, EncodedPoint};
+use sha2::Sha256;
+use signature::{BLSSigCore, BLSSignaturePop};
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use types::{Aug, Basic, Pop, PublicKey, Scheme, SecretKey, Signature};
+use zeroize::Zeroize;
+
+/// Length of a serialized secret key
+pub const BLS_SECRET_KEY_LEN: usize = 32;
+/// Length of a compressed G1 point
+pub const BLS_G1_LEN: usize = 48;
+/// Length of a compressed G2 point
+pub const BLS_G2_LEN: usize = 96;
+
+/// Success, or a valid signature
+pub const BLS_OK: c_int = 0;
+/// See BlsError::InvalidEncoding
+pub const BLS_ERR_INVALID_ENCODING: c_int = 1;
+/// See BlsError::NotInSubgroup
+pub const BLS_ERR_NOT_IN_SUBGROUP: c_int = 2;
+/// See BlsError::IdentityKey
+pub const BLS_ERR_IDENTITY_KEY: c_int = 3;
+/// See BlsError::LengthMismatch
+pub const BLS_ERR_LENGTH_MISMATCH: c_int = 4;
+/// See BlsError::DuplicateMessage
+pub const BLS_ERR_DUPLICATE_MESSAGE: c_int = 5;
+/// See BlsError::DuplicateSigner
+pub const BLS_ERR_DUPLICATE_SIGNER: c_int = 6;
+/// See BlsError::MissingSigner
+pub const BLS_ERR_MISSING_SIGNER: c_int = 7;
+/// See BlsError::SignerOutOfRange
+pub const BLS_ERR_SIGNER_OUT_OF_RANGE: c_int = 8;
+/// See BlsError::EmptyInput
+pub const BLS_ERR_EMPTY_INPUT: c_int = 9;
+/// See BlsError::PairingFailure
+pub const BLS_ERR_PAIRING_FAILURE: c_int = 10;
+/// See BlsError::InvalidSignature
+pub const BLS_ERR_INVALID_SIGNATURE: c_int = 11;
+/// See BlsError::UnknownCiphersuite
+pub const BLS_ERR_UNKNOWN_CIPHERSUITE: c_int = 12;
+/// See BlsError::InvalidDst
+pub const BLS_ERR_INVALID_DST: c_int = 13;
+/// See BlsError::InvalidPath
+pub const BLS_ERR_INVALID_PATH: c_int = 14;
+/// See BlsError::InvalidSeed
+pub const BLS_ERR_INVALID_SEED: c_int = 15;
+/// See BlsError::InvalidKeystore
+pub const BLS_ERR_INVALID_KEYSTORE: c_int = 16;
+/// See BlsError::InvalidPassword
+pub const BLS_ERR_INVALID_PASSWORD: c_int = 17;
+/// See BlsError::InvalidThreshold
+pub const BLS_ERR_INVALID_THRESHOLD: c_int = 18;
+/// See BlsError::TooFewShares
+pub const BLS_ERR_TOO_FEW_SHARES: c_int = 19;
+/// See BlsError::InvalidDkgMessage
+pub const BLS_ERR_INVALID_DKG_MESSAGE: c_int = 20;
+/// See BlsError::InvalidShare
+pub const BLS_ERR_INVALID_SHARE: c_int = 21;
+/// See BlsError::TooFewDealers
+pub const BLS_ERR_TOO_FEW_DEALERS: c_int = 22;
+/// A required pointer argument was null
+pub const BLS_ERR_NULL_POINTER: c_int = -1;
+/// An internal error; panics are caught rather than unwinding into the caller
+pub const BLS_ERR_INTERNAL: c_int = -2;
+
+type Xmd = ExpandMsgXmd<Sha256>;
+
+fn error_code(e: BlsError) -> c_int {
+    match e {
+        BlsError::InvalidEncoding => BLS_ERR_INVALID_ENCODING,
+        BlsError::NotInSubgroup => BLS_ERR_NOT_IN_SUBGROUP,
+        BlsError::IdentityKey => BLS_ERR_IDENTITY_KEY,
+        BlsError::LengthMismatch => BLS_ERR_LENGTH_MISMATCH,
+        BlsError::DuplicateMessage => BLS_ERR_DUPLICATE_MESSAGE,
+        BlsError::DuplicateSigner => BLS_ERR_DUPLICATE_SIGNER,
+        BlsError::MissingSigner => BLS_ERR_MISSING_SIGNER,
+        BlsError::SignerOutOfRange => BLS_ERR_SIGNER_OUT_OF_RANGE,
+        BlsError::EmptyInput => BLS_ERR_EMPTY_INPUT,
+        BlsError::InvalidDst => BLS_ERR_INVALID_DST,
+        BlsError::UnknownCiphersuite => BLS_ERR_UNKNOWN_CIPHERSUITE,
+        BlsError::PairingFailure => BLS_ERR_PAIRING_FAILURE,
+        BlsError::InvalidSignature => BLS_ERR_INVALID_SIGNATURE,
+        BlsError::InvalidPath => BLS_ERR_INVALID_PATH,
+        BlsError::InvalidSeed => BLS_ERR_INVALID_SEED,
+        BlsError::InvalidKeystore => BLS_ERR_INVALID_KEYSTORE,
+        BlsError::InvalidPassword => BLS_ERR_INVALID_PASSWORD,
+        BlsError::InvalidThreshold => BLS_ERR_INVALID_THRESHOLD,
+        BlsError::TooFewShares => BLS_ERR_TOO_FEW_SHARES,
+        BlsError::InvalidDkgMessage => BLS_ERR_INVALID_DKG_MESSAGE,
+        BlsError::InvalidShare => BLS_ERR_INVALID_SHARE,
+        BlsError::TooFewDealers => BLS_ERR_TOO_FEW_DEALERS,
+    }
+}
+
+// every extern "C" function's body runs in here, since unwinding across the FFI boundary is UB
+fn status<F: FnOnce() -> Result<(), c_int>>(f: F) -> c_int {
+    // outputs are only written on success, so a panic leaves nothing half-written
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => BLS_OK,
+        Ok(Err(code)) => code,
+        Err(_) => BLS_ERR_INTERNAL,
+    }
+}
+
+// borrow len bytes at ptr; null is only allowed when len is 0
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], c_int> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(BLS_ERR_NULL_POINTER)
+    } else {
+        Ok(slice::from_raw_parts(ptr, len))
+    }
+}
+
+unsafe fn output(ptr: *mut u8, bytes: &[u8]) -> Result<(), c_int> {
+    if ptr.is_null() {
+        return Err(BLS_ERR_NULL_POINTER);
+    }
+    slice::from_raw_parts_mut(ptr, bytes.len()).copy_from_slice(bytes);
+    Ok(())
+}
+
+// length of a compressed point
+fn point_len<T: CurveProjective>() -> usize {
+    <T::Affine as CurveAffine>::Compressed::size()
+}
+
+unsafe fn read_sk<G, S>(sk: *const u8) -> Result<SecretKey<G, S, Xmd>, c_int>
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    SecretKey::from_bytes(input(sk, BLS_SECRET_KEY_LEN)?).map_err(error_code)
+}
+
+unsafe fn read_pk<G, S>(pk: *const u8) -> Result<PublicKey<G, S, Xmd>, c_int>
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    PublicKey::from_bytes(input(pk, point_len::<G::PKType>())?).map_err(error_code)
+}
+
+unsafe fn read_sig<G, S>(sig: *const u8) -> Result<Signature<G, S, Xmd>, c_int>
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    Signature::from_bytes(input(sig, point_len::<G>())?).map_err(error_code)
+}
+
+unsafe fn keygen<G, S>(ikm: *const u8, ikm_len: usize, sk_out: *mut u8, pk_out: *mut u8) -> c_int
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    status(|| {
+        let sk = SecretKey::<G, S, Xmd>::keygen_with_info(input(ikm, ikm_len)?, [])
+            .ok_or(BLS_ERR_INVALID_SEED)?;
+        let mut sk_bytes = sk.to_bytes();
+        let ret = output(sk_out, &sk_bytes[..]);
+        sk_bytes.zeroize();
+        ret?;
+        output(pk_out, sk.public_key().to_bytes().as_ref())
+    })
+}
+
+unsafe fn sign<G, S>(sk: *const u8, msg: *const u8, msg_len: usize, sig_out: *mut u8) -> c_int
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    status(|| {
+        let sk = read_sk::<G, S>(sk)?;
+        let sig = sk.sign(input(msg, msg_len)?);
+        output(sig_out, sig.to_bytes().as_ref())
+    })
+}
+
+unsafe fn verify<G, S>(pk: *const u8, sig: *const u8, msg: *const u8, msg_len: usize) -> c_int
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    status(|| {
+        let pk = read_pk::<G, S>(pk)?;
+        let sig = read_sig::<G, S>(sig)?;
+        sig.try_verify(&pk, input(msg, msg_len)?)
+            .map_err(error_code)
+    })
+}
+
+unsafe fn aggregate<G, S>(sigs: *const u8, n: usize, sig_out: *mut u8) -> c_int
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    status(|| {
+        if n == 0 {
+            return Err(BLS_ERR_EMPTY_INPUT);
+        }
+        let len = point_len::<G>();
+        let sigs = input(sigs, n.checked_mul(len).ok_or(BLS_ERR_LENGTH_MISMATCH)?)?
+            .chunks(len)
+            .map(Signature::<G, S, Xmd>::from_bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error_code)?;
+        output(sig_out, Signature::aggregate(&sigs[..]).to_bytes().as_ref())
+    })
+}
+
+unsafe fn aggregate_verify<G, S>(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int
+where
+    G: BLSSigCore<Xmd>,
+    S: Scheme<G, Xmd>,
+{
+    status(|| {
+        if n == 0 {
+            return Err(BLS_ERR_EMPTY_INPUT);
+        }
+        if msgs.is_null() || msg_lens.is_null() {
+            return Err(BLS_ERR_NULL_POINTER);
+        }
+        let len = point_len::<G::PKType>();
+        let pks = input(pks, n.checked_mul(len).ok_or(BLS_ERR_LENGTH_MISMATCH)?)?
+            .chunks(len)
+            .map(PublicKey::<G, S, Xmd>::from_bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error_code)?;
+        let msgs = slice::from_raw_parts(msgs, n)
+            .iter()
+            .zip(slice::from_raw_parts(msg_lens, n))
+            .map(|(&msg, &msg_len)| input(msg, msg_len))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sig = read_sig::<G, S>(sig)?;
+        sig.try_aggregate_verify(&pks[..], &msgs[..])
+            .map_err(error_code)
+    })
+}
+
+unsafe fn pop_prove<G>(sk: *const u8, pop_out: *mut u8) -> c_int
+where
+    G: BLSSignaturePop<Xmd>,
+{
+    status(|| {
+        let sk = read_sk::<G, Pop>(sk)?;
+        output(pop_out, sk.pop_prove().to_bytes().as_ref())
+    })
+}
+
+unsafe fn pop_verify<G>(pk: *const u8, pop: *const u8) -> c_int
+where
+    G: BLSSignaturePop<Xmd>,
+{
+    status(|| {
+        let pk = read_pk::<G, Pop>(pk)?;
+        let pop = read_sig::<G, Pop>(pop)?;
+        pk.try_pop_verify(&pop).map_err(error_code)
+    })
+}
+
+/// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G2_LEN` bytes)
+/// from ikm, which must be at least 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g1_keygen(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+    pk_out: *mut u8,
+) -> c_int {
+    keygen::<G1, Basic>(ikm, ikm_len, sk_out, pk_out)
+}
+
+/// Sign msg, writing `BLS_G1_LEN` bytes to sig_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g1_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    sign::<G1, Basic>(sk, msg, msg_len, sig_out)
+}
+
+/// Verify a signature on msg
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g1_verify(
+    pk: *const u8,
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+) -> c_int {
+    verify::<G1, Basic>(pk, sig, msg, msg_len)
+}
+
+/// Aggregate n signatures, stored back to back in sigs
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g1_aggregate(
+    sigs: *const u8,
+    n: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    aggregate::<G1, Basic>(sigs, n, sig_out)
+}
+
+/// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g1_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int {
+    aggregate_verify::<G1, Basic>(pks, msgs, msg_lens, n, sig)
+}
+
+/// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G1_LEN` bytes)
+/// from ikm, which must be at least 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g2_keygen(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+    pk_out: *mut u8,
+) -> c_int {
+    keygen::<G2, Basic>(ikm, ikm_len, sk_out, pk_out)
+}
+
+/// Sign msg, writing `BLS_G2_LEN` bytes to sig_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g2_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    sign::<G2, Basic>(sk, msg, msg_len, sig_out)
+}
+
+/// Verify a signature on msg
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g2_verify(
+    pk: *const u8,
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+) -> c_int {
+    verify::<G2, Basic>(pk, sig, msg, msg_len)
+}
+
+/// Aggregate n signatures, stored back to back in sigs
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g2_aggregate(
+    sigs: *const u8,
+    n: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    aggregate::<G2, Basic>(sigs, n, sig_out)
+}
+
+/// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+#[no_mangle]
+pub unsafe extern "C" fn bls_basic_g2_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int {
+    aggregate_verify::<G2, Basic>(pks, msgs, msg_lens, n, sig)
+}
+
+/// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G2_LEN` bytes)
+/// from ikm, which must be at least 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g1_keygen(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+    pk_out: *mut u8,
+) -> c_int {
+    keygen::<G1, Aug>(ikm, ikm_len, sk_out, pk_out)
+}
+
+/// Sign msg, writing `BLS_G1_LEN` bytes to sig_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g1_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    sign::<G1, Aug>(sk, msg, msg_len, sig_out)
+}
+
+/// Verify a signature on msg
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g1_verify(
+    pk: *const u8,
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+) -> c_int {
+    verify::<G1, Aug>(pk, sig, msg, msg_len)
+}
+
+/// Aggregate n signatures, stored back to back in sigs
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g1_aggregate(
+    sigs: *const u8,
+    n: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    aggregate::<G1, Aug>(sigs, n, sig_out)
+}
+
+/// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g1_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int {
+    aggregate_verify::<G1, Aug>(pks, msgs, msg_lens, n, sig)
+}
+
+/// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G1_LEN` bytes)
+/// from ikm, which must be at least 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g2_keygen(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+    pk_out: *mut u8,
+) -> c_int {
+    keygen::<G2, Aug>(ikm, ikm_len, sk_out, pk_out)
+}
+
+/// Sign msg, writing `BLS_G2_LEN` bytes to sig_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g2_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    sign::<G2, Aug>(sk, msg, msg_len, sig_out)
+}
+
+/// Verify a signature on msg
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g2_verify(
+    pk: *const u8,
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+) -> c_int {
+    verify::<G2, Aug>(pk, sig, msg, msg_len)
+}
+
+/// Aggregate n signatures, stored back to back in sigs
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g2_aggregate(
+    sigs: *const u8,
+    n: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    aggregate::<G2, Aug>(sigs, n, sig_out)
+}
+
+/// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+#[no_mangle]
+pub unsafe extern "C" fn bls_aug_g2_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int {
+    aggregate_verify::<G2, Aug>(pks, msgs, msg_lens, n, sig)
+}
+
+/// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G2_LEN` bytes)
+/// from ikm, which must be at least 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_keygen(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+    pk_out: *mut u8,
+) -> c_int {
+    keygen::<G1, Pop>(ikm, ikm_len, sk_out, pk_out)
+}
+
+/// Sign msg, writing `BLS_G1_LEN` bytes to sig_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    sign::<G1, Pop>(sk, msg, msg_len, sig_out)
+}
+
+/// Verify a signature on msg
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_verify(
+    pk: *const u8,
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+) -> c_int {
+    verify::<G1, Pop>(pk, sig, msg, msg_len)
+}
+
+/// Aggregate n signatures, stored back to back in sigs
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_aggregate(
+    sigs: *const u8,
+    n: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    aggregate::<G1, Pop>(sigs, n, sig_out)
+}
+
+/// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int {
+    aggregate_verify::<G1, Pop>(pks, msgs, msg_lens, n, sig)
+}
+
+/// Prove possession of sk, writing `BLS_G1_LEN` bytes to pop_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_pop_prove(sk: *const u8, pop_out: *mut u8) -> c_int {
+    pop_prove::<G1>(sk, pop_out)
+}
+
+/// Check a proof of possession for pk
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g1_pop_verify(pk: *const u8, pop: *const u8) -> c_int {
+    pop_verify::<G1>(pk, pop)
+}
+
+/// Generate a secret key (`BLS_SECRET_KEY_LEN` bytes) and public key (`BLS_G1_LEN` bytes)
+/// from ikm, which must be at least 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_keygen(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+    pk_out: *mut u8,
+) -> c_int {
+    keygen::<G2, Pop>(ikm, ikm_len, sk_out, pk_out)
+}
+
+/// Sign msg, writing `BLS_G2_LEN` bytes to sig_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    sign::<G2, Pop>(sk, msg, msg_len, sig_out)
+}
+
+/// Verify a signature on msg
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_verify(
+    pk: *const u8,
+    sig: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+) -> c_int {
+    verify::<G2, Pop>(pk, sig, msg, msg_len)
+}
+
+/// Aggregate n signatures, stored back to back in sigs
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_aggregate(
+    sigs: *const u8,
+    n: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    aggregate::<G2, Pop>(sigs, n, sig_out)
+}
+
+/// Verify an aggregate signature on msgs[i] (of length msg_lens[i]) under the ith key in pks
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    n: usize,
+    sig: *const u8,
+) -> c_int {
+    aggregate_verify::<G2, Pop>(pks, msgs, msg_lens, n, sig)
+}
+
+/// Prove possession of sk, writing `BLS_G2_LEN` bytes to pop_out
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_pop_prove(sk: *const u8, pop_out: *mut u8) -> c_int {
+    pop_prove::<G2>(sk, pop_out)
+}
+
+/// Check a proof of possession for pk
+#[no_mangle]
+pub unsafe extern "C" fn bls_pop_g2_pop_verify(pk: *const u8, pop: *const u8) -> c_int {
+    pop_verify::<G2>(pk, pop)
+}
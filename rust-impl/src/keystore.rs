@@ -0,0 +1,462 @@
+/*!
+Encrypted keystores (EIP-2335)
+
+A keystore holds a secret key encrypted under a password, in the JSON format shared by Ethereum
+BLS tooling. The password is normalized (NFKD, then control codes removed) and stretched with
+scrypt or PBKDF2-HMAC-SHA256 into a 32-byte decryption key. The first half of that key encrypts
+the big-endian secret scalar with AES-128-CTR; the second half, hashed with the ciphertext,
+gives a SHA-256 checksum that detects a wrong password before anything is decrypted.
+*/
+
+use aes::Aes128;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use ctr::Ctr128BE;
+use error::BlsError;
+use hmac::Hmac;
+use pairing_plus::hash_to_field::ExpandMsg;
+use pbkdf2::pbkdf2;
+use rand_core::RngCore;
+use scrypt::{scrypt, ScryptParams};
+use serde_json;
+use sha2::{Digest, Sha256};
+use signature::BLSSigCore;
+use types::{Scheme, SecretKey};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroize;
+
+/// The keystore format version
+const VERSION: u32 = 4;
+
+/// Length of the key derived from the password
+const DKLEN: usize = 32;
+
+/// Length of the KDF salt in new keystores
+const SALT_LEN: usize = 32;
+
+/// Length of the AES-128-CTR IV
+const IV_LEN: usize = 16;
+
+/// The key derivation function that stretches a keystore's password
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    /// scrypt with cost n (a power of two), block size r, and parallelism p
+    Scrypt {
+        /// CPU/memory cost
+        n: u32,
+        /// block size
+        r: u32,
+        /// parallelism
+        p: u32,
+    },
+    /// PBKDF2 with HMAC-SHA256 and c iterations
+    Pbkdf2 {
+        /// iteration count
+        c: u32,
+    },
+}
+
+impl Default for Kdf {
+    /// scrypt with the parameters used in EIP-2335's examples
+    fn default() -> Self {
+        Kdf::Scrypt {
+            n: 262144,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl Kdf {
+    // derive the decryption key from the normalized password
+    fn derive(&self, password: &[u8], salt: &[u8]) -> Result<Vec<u8>, BlsError> {
+        let mut key = vec![0u8; DKLEN];
+        match *self {
+            Kdf::Scrypt { n, r, p } => {
+                if !n.is_power_of_two() || n < 2 {
+                    return Err(BlsError::InvalidKeystore);
+                }
+                let params = ScryptParams::new(n.trailing_zeros() as u8, r, p)
+                    .map_err(|_| BlsError::InvalidKeystore)?;
+                scrypt(password, salt, &params, &mut key[..])
+                    .map_err(|_| BlsError::InvalidKeystore)?;
+            }
+            Kdf::Pbkdf2 { c } => {
+                if c == 0 {
+                    return Err(BlsError::InvalidKeystore);
+                }
+                pbkdf2::<Hmac<Sha256>>(password, salt, c as usize, &mut key[..]);
+            }
+        }
+        Ok(key)
+    }
+}
+
+/// Normalize a password as EIP-2335 requires: NFKD, then strip the C0, C1, and Delete control codes
+pub fn normalize_password(password: &str) -> Vec<u8> {
+    password
+        .nfkd()
+        .filter(|&c| !matches!(c, '\u{00}'..='\u{1f}' | '\u{7f}'..='\u{9f}'))
+        .collect::<String>()
+        .into_bytes()
+}
+
+/// An EIP-2335 keystore: a secret key encrypted under a password
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keystore {
+    kdf: Kdf,
+    salt: Vec<u8>,
+    checksum: Vec<u8>,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+    description: String,
+    pubkey: Vec<u8>,
+    path: String,
+    uuid: String,
+}
+
+impl Keystore {
+    /// Encrypt a secret key under a password
+    /// * path is the EIP-2334 path the key was derived at, or empty
+    /// * rng supplies the salt, the IV, and the UUID
+    pub fn encrypt<G, S, X, R>(
+        sk: &SecretKey<G, S, X>,
+        password: &str,
+        path: &str,
+        kdf: Kdf,
+        rng: &mut R,
+    ) -> Result<Self, BlsError>
+    where
+        G: BLSSigCore<X>,
+        S: Scheme<G, X>,
+        X: ExpandMsg,
+        R: RngCore,
+    {
+        let mut salt = vec![0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt[..]);
+        let mut iv = vec![0u8; IV_LEN];
+        rng.fill_bytes(&mut iv[..]);
+        let mut uuid = [0u8; 16];
+        rng.fill_bytes(&mut uuid[..]);
+
+        let mut password = normalize_password(password);
+        let key = kdf.derive(&password[..], &salt[..]);
+        password.zeroize();
+        let mut key = key?;
+
+        let mut secret = sk.to_bytes();
+        let mut ciphertext = secret.to_vec();
+        secret.zeroize();
+        _aes_128_ctr(&key[..16], &iv[..], &mut ciphertext[..]);
+        let checksum = _checksum(&key[16..], &ciphertext[..]);
+        key.zeroize();
+
+        Ok(Keystore {
+            kdf,
+            salt,
+            checksum,
+            iv,
+            ciphertext,
+            description: String::new(),
+            pubkey: sk.public_key().to_bytes().as_ref().to_vec(),
+            path: path.to_string(),
+            uuid: _uuid_v4(uuid),
+        })
+    }
+
+    /// Decrypt the secret key
+    ///
+    /// This fails with InvalidPassword if the checksum does not match, and with InvalidKeystore
+    /// if the keystore records a public key that does not belong to the decrypted secret key
+    /// (for example, because it was made for the other group).
+    pub fn decrypt<G, S, X>(&self, password: &str) -> Result<SecretKey<G, S, X>, BlsError>
+    where
+        G: BLSSigCore<X>,
+        S: Scheme<G, X>,
+        X: ExpandMsg,
+    {
+        let mut password = normalize_password(password);
+        let key = self.kdf.derive(&password[..], &self.salt[..]);
+        password.zeroize();
+        let mut key = key?;
+
+        let checksum = _checksum(&key[16..], &self.ciphertext[..]);
+        if !_ct_eq(&checksum[..], &self.checksum[..]) {
+            key.zeroize();
+            return Err(BlsError::InvalidPassword);
+        }
+        let mut secret = self.ciphertext.clone();
+        _aes_128_ctr(&key[..16], &self.iv[..], &mut secret[..]);
+        key.zeroize();
+        let sk = SecretKey::<G, S, X>::from_bytes(&secret[..]);
+        secret.zeroize();
+        let sk = sk?;
+
+        let pubkey = sk.public_key().to_bytes();
+        if !self.pubkey.is_empty() && &self.pubkey[..] != pubkey.as_ref() {
+            return Err(BlsError::InvalidKeystore);
+        }
+        Ok(sk)
+    }
+
+    /// Parse a keystore from its JSON encoding
+    pub fn from_json(json: &str) -> Result<Self, BlsError> {
+        let json: json::Keystore =
+            serde_json::from_str(json).map_err(|_| BlsError::InvalidKeystore)?;
+        json.into_keystore()
+    }
+
+    /// The JSON encoding of this keystore
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&json::Keystore::from_keystore(self))
+            .expect("a keystore always serializes")
+    }
+
+    /// Replace the free-form description
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// The key derivation function protecting the password
+    pub fn kdf(&self) -> Kdf {
+        self.kdf
+    }
+
+    /// The free-form description
+    pub fn description(&self) -> &str {
+        &self.description[..]
+    }
+
+    /// The encoded public key of the stored secret key
+    pub fn pubkey(&self) -> &[u8] {
+        &self.pubkey[..]
+    }
+
+    /// The EIP-2334 path of the stored key, or empty
+    pub fn path(&self) -> &str {
+        &self.path[..]
+    }
+
+    /// The keystore's UUID
+    pub fn uuid(&self) -> &str {
+        &self.uuid[..]
+    }
+}
+
+// apply the AES-128-CTR keystream in place
+fn _aes_128_ctr(key: &[u8], iv: &[u8], data: &mut [u8]) {
+    let mut cipher = Ctr128BE::<Aes128>::new(key.into(), iv.into());
+    cipher.apply_keystream(data);
+}
+
+// SHA256(DK[16..32] || cipher_message)
+fn _checksum(key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut h = Sha256::new();
+    h.input(key);
+    h.input(ciphertext);
+    h.result().to_vec()
+}
+
+// equality without an early exit
+fn _ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b.iter())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+// format 16 random bytes as a version 4 (random) UUID
+fn _uuid_v4(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = _to_hex(&bytes[..]);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn _to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn _from_hex(hex: &str) -> Result<Vec<u8>, BlsError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(BlsError::InvalidKeystore);
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or(BlsError::InvalidKeystore)
+        })
+        .collect()
+}
+
+// The JSON layout of a keystore
+mod json {
+    use super::{_from_hex, _to_hex, Kdf, DKLEN, VERSION};
+    use error::BlsError;
+    use serde_json::{Map, Value};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Keystore {
+        crypto: Crypto,
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        pubkey: String,
+        path: String,
+        uuid: String,
+        version: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Crypto {
+        kdf: Module<KdfParams>,
+        checksum: Module<Map<String, Value>>,
+        cipher: Module<CipherParams>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Module<P> {
+        function: String,
+        params: P,
+        message: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum KdfParams {
+        Scrypt {
+            dklen: usize,
+            n: u32,
+            p: u32,
+            r: u32,
+            salt: String,
+        },
+        Pbkdf2 {
+            dklen: usize,
+            c: u32,
+            prf: String,
+            salt: String,
+        },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct CipherParams {
+        iv: String,
+    }
+
+    impl Keystore {
+        pub(super) fn into_keystore(self) -> Result<super::Keystore, BlsError> {
+            let Crypto {
+                kdf,
+                checksum,
+                cipher,
+            } = self.crypto;
+            let kdf_message = kdf.message;
+            let (kdf, dklen, salt) = match (&kdf.function[..], kdf.params) {
+                (
+                    "scrypt",
+                    KdfParams::Scrypt {
+                        dklen,
+                        n,
+                        p,
+                        r,
+                        salt,
+                    },
+                ) => (Kdf::Scrypt { n, r, p }, dklen, salt),
+                (
+                    "pbkdf2",
+                    KdfParams::Pbkdf2 {
+                        dklen,
+                        c,
+                        prf,
+                        salt,
+                    },
+                ) if prf == "hmac-sha256" => (Kdf::Pbkdf2 { c }, dklen, salt),
+                _ => return Err(BlsError::InvalidKeystore),
+            };
+            if self.version != VERSION
+                || dklen != DKLEN
+                || !kdf_message.is_empty()
+                || checksum.function != "sha256"
+                || !checksum.params.is_empty()
+                || cipher.function != "aes-128-ctr"
+            {
+                return Err(BlsError::InvalidKeystore);
+            }
+            let iv = _from_hex(&cipher.params.iv[..])?;
+            if iv.len() != super::IV_LEN {
+                return Err(BlsError::InvalidKeystore);
+            }
+            Ok(super::Keystore {
+                kdf,
+                salt: _from_hex(&salt[..])?,
+                checksum: _from_hex(&checksum.message[..])?,
+                iv,
+                ciphertext: _from_hex(&cipher.message[..])?,
+                description: self.description,
+                pubkey: _from_hex(&self.pubkey[..])?,
+                path: self.path,
+                uuid: self.uuid,
+            })
+        }
+
+        pub(super) fn from_keystore(ks: &super::Keystore) -> Self {
+            let salt = _to_hex(&ks.salt[..]);
+            let params = match ks.kdf {
+                Kdf::Scrypt { n, r, p } => KdfParams::Scrypt {
+                    dklen: DKLEN,
+                    n,
+                    p,
+                    r,
+                    salt,
+                },
+                Kdf::Pbkdf2 { c } => KdfParams::Pbkdf2 {
+                    dklen: DKLEN,
+                    c,
+                    prf: "hmac-sha256".to_string(),
+                    salt,
+                },
+            };
+            let function = match ks.kdf {
+                Kdf::Scrypt { .. } => "scrypt",
+                Kdf::Pbkdf2 { .. } => "pbkdf2",
+            };
+            Keystore {
+                crypto: Crypto {
+                    kdf: Module {
+                        function: function.to_string(),
+                        params,
+                        message: String::new(),
+                    },
+                    checksum: Module {
+                        function: "sha256".to_string(),
+                        params: Map::new(),
+                        message: _to_hex(&ks.checksum[..]),
+                    },
+                    cipher: Module {
+                        function: "aes-128-ctr".to_string(),
+                        params: CipherParams {
+                            iv: _to_hex(&ks.iv[..]),
+                        },
+                        message: _to_hex(&ks.ciphertext[..]),
+                    },
+                },
+                description: ks.description.clone(),
+                pubkey: _to_hex(&ks.pubkey[..]),
+                path: ks.path.clone(),
+                uuid: ks.uuid.clone(),
+                version: VERSION,
+            }
+        }
+    }
+}
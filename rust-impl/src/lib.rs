@@ -1,5 +1,5 @@
-#![cfg_attr(feature = "cargo-clippy", deny(warnings))]
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::unreadable_literal))]
+#![cfg_attr(clippy, deny(warnings))]
+#![allow(clippy::unreadable_literal)]
 #![deny(missing_debug_implementations)]
 #![deny(missing_docs)]
 
@@ -10,21 +10,72 @@
  It is based upon the pairing crate's implementation of BLS12-381.
 */
 
+extern crate aes;
 #[cfg(test)]
 extern crate byteorder;
+extern crate ctr;
 extern crate ff_zeroize as ff;
 
 #[cfg(test)]
+#[macro_use]
 extern crate hex_literal;
 extern crate hkdf;
+extern crate hmac;
 extern crate pairing_plus;
+extern crate pbkdf2;
 #[cfg(test)]
 extern crate rand;
+extern crate rand_core;
+#[cfg(test)]
+extern crate rand_xorshift;
+extern crate scrypt;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate sha2;
+extern crate sha3;
+extern crate unicode_normalization;
+extern crate zeroize;
 
+mod aggregator;
+mod bitfield;
+mod committee;
+mod ct;
+mod derive;
+mod dkg;
+mod dst;
+mod encoding;
+mod error;
+pub mod ffi;
+mod keystore;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod registry;
 mod signature;
+mod stream;
+mod threshold;
+mod types;
 
-pub use signature::{BLSSigCore, BLSSignatureAug, BLSSignatureBasic, BLSSignaturePop};
+pub use aggregator::Aggregator;
+pub use bitfield::Bitfield;
+pub use committee::Committee;
+pub use derive::{derive_child_sk, derive_master_sk, derive_path, parse_path};
+pub use dkg::{Commitment, Complaint, Dkg, DkgOutput, EncryptedShare, Justification};
+pub use dst::{CustomScheme, ExpandMsgDst, SchemeBuilder, MAX_DST_LEN};
+pub use encoding::PointEncoding;
+pub use error::BlsError;
+pub use keystore::{normalize_password, Kdf, Keystore};
+pub use registry::{ciphersuite, ciphersuites, Ciphersuite};
+pub use signature::{
+    xprime_from_ikm, BLSSigCore, BLSSignatureAug, BLSSignatureBasic, BLSSignaturePop,
+};
+pub use stream::{hash_message_stream, ExpandMsgStream, FromUniformBytes, MessageStream};
+pub use threshold::{split, PartialSignature, SecretKeyShare, SplitKey, ThresholdPublicKey};
+pub use types::{
+    Aug, Basic, HashedMessage, Pop, PrehashScheme, PreparedPublicKey, PublicKey, Scheme, SecretKey,
+    Signature,
+};
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,49 @@
+/*!
+Worker threads for verification (the `parallel` feature)
+
+A multi-pairing's Miller loop is a product over the pairs, so it can be split into chunks that are
+hashed, prepared, and run on separate threads; multiplying the partial results gives exactly the
+serial Miller loop's output, and a single final exponentiation finishes the job.
+*/
+
+use std::cmp;
+use std::ops::Range;
+use std::panic;
+use std::thread;
+
+/// Fewest pairs worth a thread of their own
+const MIN_CHUNK: usize = 32;
+
+/// Run f on chunks that cover 0..n, each on its own thread, and return the results in order
+pub(crate) fn _par_chunks<T, F>(n: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(Range<usize>) -> T + Sync,
+{
+    let chunk = cmp::max(MIN_CHUNK, n.div_ceil(_workers()));
+    if n <= chunk {
+        return vec![f(0..n)];
+    }
+    let f = &f;
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..n)
+            .step_by(chunk)
+            .map(|start| s.spawn(move || f(start..cmp::min(n, start + chunk))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+// one worker per CPU
+fn _workers() -> usize {
+    // unit tests always split the work, so that the multi-threaded path is exercised on any machine
+    if cfg!(test) {
+        return 4;
+    }
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
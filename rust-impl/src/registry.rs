@@ -0,0 +1,125 @@
+/*!
+Runtime lookup of ciphersuites by ID
+
+The schemes in this crate are selected at compile time by type parameters. When the ciphersuite
+is only known at runtime, e.g., because it arrives over the wire, use `ciphersuite` to look up
+a `Ciphersuite` object by its ID. Keys and signatures are passed as byte strings: secret keys
+in big-endian form, and public keys and signatures in compressed form.
+*/
+
+use error::BlsError;
+use pairing_plus::bls12_381::{G1, G2};
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd, ExpandMsgXof};
+use sha2::{Sha256, Sha512};
+use sha3::{Shake128, Shake256};
+use signature::BLSSigCore;
+use std::fmt;
+use std::marker::PhantomData;
+use types::{Aug, Basic, Pop, PublicKey, Scheme, SchemeMarker, SecretKey, Signature};
+
+/// A ciphersuite whose group, scheme, and hash are chosen at runtime
+pub trait Ciphersuite: fmt::Debug + Sync {
+    /// The ciphersuite ID, e.g., `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`
+    fn id(&self) -> &'static [u8];
+
+    /// The public key corresponding to a secret key
+    fn public_key(&self, sk: &[u8]) -> Result<Vec<u8>, BlsError>;
+
+    /// Sign a message
+    fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>, BlsError>;
+
+    /// Verify a signature on msg under pk
+    fn verify(&self, pk: &[u8], msg: &[u8], sig: &[u8]) -> Result<(), BlsError>;
+
+    /// Aggregate signatures
+    fn aggregate(&self, sigs: &[&[u8]]) -> Result<Vec<u8>, BlsError>;
+
+    /// Verify an aggregate signature, where msgs[i] was signed under pks[i]
+    fn aggregate_verify(&self, pks: &[&[u8]], msgs: &[&[u8]], sig: &[u8]) -> Result<(), BlsError>;
+}
+
+// the Ciphersuite for scheme S with signatures in G and expander X
+struct Suite<G, S, X>(SchemeMarker<G, S, X>);
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> fmt::Debug for Suite<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ciphersuite({})", String::from_utf8_lossy(S::CSUITE))
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> Ciphersuite for Suite<G, S, X> {
+    fn id(&self) -> &'static [u8] {
+        S::CSUITE
+    }
+
+    fn public_key(&self, sk: &[u8]) -> Result<Vec<u8>, BlsError> {
+        let sk = SecretKey::<G, S, X>::from_bytes(sk)?;
+        Ok(sk.public_key().to_bytes().as_ref().to_vec())
+    }
+
+    fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>, BlsError> {
+        let sk = SecretKey::<G, S, X>::from_bytes(sk)?;
+        Ok(sk.sign(msg).to_bytes().as_ref().to_vec())
+    }
+
+    fn verify(&self, pk: &[u8], msg: &[u8], sig: &[u8]) -> Result<(), BlsError> {
+        let pk = PublicKey::<G, S, X>::from_bytes(pk)?;
+        Signature::<G, S, X>::from_bytes(sig)?.try_verify(&pk, msg)
+    }
+
+    fn aggregate(&self, sigs: &[&[u8]]) -> Result<Vec<u8>, BlsError> {
+        if sigs.is_empty() {
+            return Err(BlsError::EmptyInput);
+        }
+        let sigs = sigs
+            .iter()
+            .map(|sig| Signature::<G, S, X>::from_bytes(sig))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Signature::aggregate(&sigs[..]).to_bytes().as_ref().to_vec())
+    }
+
+    fn aggregate_verify(&self, pks: &[&[u8]], msgs: &[&[u8]], sig: &[u8]) -> Result<(), BlsError> {
+        let pks = pks
+            .iter()
+            .map(|pk| PublicKey::<G, S, X>::from_bytes(pk))
+            .collect::<Result<Vec<_>, _>>()?;
+        Signature::<G, S, X>::from_bytes(sig)?.try_aggregate_verify(&pks[..], msgs)
+    }
+}
+
+// every scheme in both groups, for each of the given expanders
+macro_rules! registry {
+    ($($x:ty),*) => {
+        static CIPHERSUITES: &[&dyn Ciphersuite] = &[
+            $(
+                &Suite::<G1, Basic, $x>(PhantomData),
+                &Suite::<G1, Aug, $x>(PhantomData),
+                &Suite::<G1, Pop, $x>(PhantomData),
+                &Suite::<G2, Basic, $x>(PhantomData),
+                &Suite::<G2, Aug, $x>(PhantomData),
+                &Suite::<G2, Pop, $x>(PhantomData),
+            )*
+        ];
+    };
+}
+
+registry!(
+    ExpandMsgXmd<Sha256>,
+    ExpandMsgXmd<Sha512>,
+    ExpandMsgXof<Shake128>,
+    ExpandMsgXof<Shake256>
+);
+
+/// Look up a ciphersuite by its ID
+pub fn ciphersuite<B: AsRef<[u8]>>(id: B) -> Result<&'static dyn Ciphersuite, BlsError> {
+    CIPHERSUITES
+        .iter()
+        .find(|cs| cs.id() == id.as_ref())
+        .cloned()
+        .ok_or(BlsError::UnknownCiphersuite)
+}
+
+/// All ciphersuites known to the registry
+pub fn ciphersuites() -> &'static [&'static dyn Ciphersuite] {
+    CIPHERSUITES
+}
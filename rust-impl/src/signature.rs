@@ -2,24 +2,38 @@
 BLS signatures
 */
 
-use ff::Field;
+use ct::{ct_mul_assign, CtSelect};
+use encoding::PointEncoding;
+use error::BlsError;
+use ff::{Field, PrimeFieldRepr};
 use hkdf::Hkdf;
-use pairing_plus::bls12_381::{Bls12, Fq12, Fr, G1, G2};
+use pairing_plus::bls12_381::{Bls12, Fq12, Fr, FrRepr, G1Prepared, G2Prepared, G1, G2};
 use pairing_plus::hash_to_curve::HashToCurve;
-use pairing_plus::hash_to_field::{BaseFromRO, ExpandMsg, ExpandMsgXmd};
+use pairing_plus::hash_to_field::{BaseFromRO, ExpandMsg, ExpandMsgXmd, ExpandMsgXof};
 use pairing_plus::serdes::SerDes;
-use pairing_plus::{CurveAffine, CurveProjective, Engine};
+use pairing_plus::{CurveAffine, CurveProjective, Engine, SubgroupCheck};
+#[cfg(feature = "parallel")]
+use parallel::_par_chunks;
+use rand_core::RngCore;
 use sha2::digest::generic_array::typenum::{U48, U96};
 use sha2::digest::generic_array::{ArrayLength, GenericArray};
-use sha2::Sha256;
-use std::collections::HashSet;
-use std::io::Cursor;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::{Shake128, Shake256};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::ptr;
+use std::sync::{atomic, OnceLock};
 use std::vec::Vec;
+use zeroize::Zeroize;
+
+/// Salt for HKDF in key generation
+const SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
 
 /// Hash a secret key sk to the secret exponent x'; then (PK, SK) = (g^{x'}, x').
-// NOTE: this implementation leaves the key_info parameter as the default empty string
+// NOTE: this is the KeyGen from earlier drafts, kept so that the test vectors still apply.
+//       It leaves key_info empty, accepts short inputs, and does not re-hash the salt.
+//       New code should use xprime_from_ikm instead.
 pub fn xprime_from_sk<B: AsRef<[u8]>>(msg: B) -> Fr {
-    const SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
     // copy of `msg` with appended zero byte
     let mut msg_prime = Vec::<u8>::with_capacity(msg.as_ref().len() + 1);
     msg_prime.extend_from_slice(msg.as_ref());
@@ -29,7 +43,54 @@ pub fn xprime_from_sk<B: AsRef<[u8]>>(msg: B) -> Fr {
     assert!(Hkdf::<Sha256>::new(Some(SALT), &msg_prime[..])
         .expand(&[0, 48], &mut result)
         .is_ok());
-    Fr::from_okm(&result)
+    let x_prime = Fr::from_okm(&result);
+    msg_prime.zeroize();
+    result.as_mut_slice().zeroize();
+    x_prime
+}
+
+/// KeyGen(IKM, key_info) from the current draft: derive the secret scalar from input keying material.
+/// Returns None if IKM is shorter than 32 bytes.
+pub fn xprime_from_ikm<B: AsRef<[u8]>, I: AsRef<[u8]>>(ikm: B, key_info: I) -> Option<Fr> {
+    const L: usize = 48;
+    if ikm.as_ref().len() < 32 {
+        return None;
+    }
+    // IKM || I2OSP(0, 1)
+    let mut ikm_prime = Vec::<u8>::with_capacity(ikm.as_ref().len() + 1);
+    ikm_prime.extend_from_slice(ikm.as_ref());
+    ikm_prime.extend_from_slice(&[0]);
+    // key_info || I2OSP(L, 2)
+    let mut info_prime = Vec::<u8>::with_capacity(key_info.as_ref().len() + 2);
+    info_prime.extend_from_slice(key_info.as_ref());
+    info_prime.extend_from_slice(&[(L >> 8) as u8, L as u8]);
+
+    // NOTE: hkdf 0.8 holds the PRK inside Hkdf and does not wipe it on drop;
+    //       only the buffers owned here are zeroized.
+    let mut salt = Sha256::digest(SALT);
+    loop {
+        let mut result = GenericArray::<u8, U48>::default();
+        assert!(Hkdf::<Sha256>::new(Some(&salt[..]), &ikm_prime[..])
+            .expand(&info_prime[..], &mut result)
+            .is_ok());
+        let sk = Fr::from_okm(&result);
+        result.as_mut_slice().zeroize();
+        if !sk.is_zero() {
+            ikm_prime.zeroize();
+            return Some(sk);
+        }
+        // SK == 0: re-hash the salt and try again
+        salt = Sha256::digest(&salt[..]);
+    }
+}
+
+/// Overwrite a secret scalar with zero
+// Scalars are Copy, so this cannot reach copies made elsewhere; functions that take a secret
+// scalar by value call this on their own copy before returning.
+pub(crate) fn _wipe_scalar<F: Field>(x: &mut F) {
+    // volatile, so that the compiler cannot elide the write to memory that is about to die
+    unsafe { ptr::write_volatile(x, F::zero()) };
+    atomic::compiler_fence(atomic::Ordering::SeqCst);
 }
 
 // multi-point-addition helper: used in aggregate and in PoP verify
@@ -44,11 +105,284 @@ fn _agg_help<T: CurveProjective>(ins: &[T]) -> T {
 /// Alias for the scalar type corresponding to a CurveProjective type
 type ScalarT<PtT> = <PtT as CurveProjective>::Scalar;
 
+/// Alias for a public key prepared for the pairing
+pub(crate) type PKPrepared<T, X> =
+    <<<T as BLSSigCore<X>>::PKType as CurveProjective>::Affine as CurveAffine>::Prepared;
+
+// -g1, prepared once for the pairing checks
+fn _neg_g1_prepared() -> &'static G1Prepared {
+    static NEG_G1: OnceLock<G1Prepared> = OnceLock::new();
+    NEG_G1.get_or_init(|| {
+        let mut tmp = G1::one();
+        tmp.negate();
+        tmp.into_affine().prepare()
+    })
+}
+
+// -g2, prepared once for the pairing checks
+fn _neg_g2_prepared() -> &'static G2Prepared {
+    static NEG_G2: OnceLock<G2Prepared> = OnceLock::new();
+    NEG_G2.get_or_init(|| {
+        let mut tmp = G2::one();
+        tmp.negate();
+        tmp.into_affine().prepare()
+    })
+}
+
+// AggregateVerify preconditions: one message per public key, and at least one of each
+pub(crate) fn _check_agg_inputs(n_pks: usize, n_msgs: usize) -> Result<(), BlsError> {
+    if n_pks != n_msgs {
+        Err(BlsError::LengthMismatch)
+    } else if n_pks == 0 {
+        Err(BlsError::EmptyInput)
+    } else {
+        Ok(())
+    }
+}
+
+// batch verification preconditions: AggregateVerify's, plus one signature per message
+fn _check_batch_inputs(n_pks: usize, n_msgs: usize, n_sigs: usize) -> Result<(), BlsError> {
+    _check_agg_inputs(n_pks, n_msgs)?;
+    if n_sigs != n_msgs {
+        return Err(BlsError::LengthMismatch);
+    }
+    Ok(())
+}
+
+// random nonzero 128-bit scalar for batch verification
+fn _batch_randomizer<R: RngCore>(rng: &mut R) -> FrRepr {
+    loop {
+        let ret = FrRepr([rng.next_u64(), rng.next_u64(), 0, 0]);
+        if !ret.is_zero() {
+            return ret;
+        }
+    }
+}
+
+// the final exponentiation of a Miller loop, which must give 1 for the check to pass
+fn _final_check(f: &Fq12) -> Result<(), BlsError> {
+    match Bls12::final_exponentiation(f) {
+        None => Err(BlsError::PairingFailure),
+        Some(pairingproduct) if pairingproduct == Fq12::one() => Ok(()),
+        Some(_) => Err(BlsError::InvalidSignature),
+    }
+}
+
+// the pairing check: the product of e(p, q) over the pairs must be 1
+fn _pairing_check<'a>(pqz: &'a [(&'a G1Prepared, &'a G2Prepared)]) -> Result<(), BlsError> {
+    _final_check(&Bls12::miller_loop(pqz))
+}
+
+// multiply the partial Miller loops into f, then finish the pairing check as the serial code does
+#[cfg(feature = "parallel")]
+fn _par_pairing_check(mut f: Fq12, partials: Vec<Fq12>) -> Result<(), BlsError> {
+    for partial in partials {
+        f.mul_assign(&partial);
+    }
+    _final_check(&f)
+}
+
+// AggregateVerify's pairing check with signatures in G1: e(h_i, pk_i) for each i, and e(sig, -g2)
+fn _g1_aggregate_check(hashes: &[G1], pks: &[G2], sig: G1) -> Result<(), BlsError> {
+    let pvec: Vec<G1Prepared> = hashes.iter().map(|h| h.into_affine().prepare()).collect();
+    let qvec: Vec<G2Prepared> = pks.iter().map(|pk| pk.into_affine().prepare()).collect();
+    let sig = sig.into_affine().prepare();
+    // miller_loop requires an iter to tuple refs, not tuples
+    let mut pqz: Vec<_> = pvec.iter().zip(qvec.iter()).collect();
+    pqz.push((&sig, _neg_g2_prepared()));
+    _pairing_check(&pqz[..])
+}
+
+// AggregateVerify's pairing check with signatures in G2: e(pk_i, h_i) for each i, and e(-g1, sig)
+fn _g2_aggregate_check(hashes: &[G2], pks: &[G1], sig: G2) -> Result<(), BlsError> {
+    let pvec: Vec<G1Prepared> = pks.iter().map(|pk| pk.into_affine().prepare()).collect();
+    let qvec: Vec<G2Prepared> = hashes.iter().map(|h| h.into_affine().prepare()).collect();
+    let sig = sig.into_affine().prepare();
+    let mut pqz: Vec<_> = pvec.iter().zip(qvec.iter()).collect();
+    pqz.push((_neg_g1_prepared(), &sig));
+    _pairing_check(&pqz[..])
+}
+
+// Equal messages can share a pairing, since e(H(m), pk_1) * e(H(m), pk_2) = e(H(m), pk_1 + pk_2).
+// Returns the distinct messages in order of first appearance, each with the sum of its keys.
+fn _group_by_message<'a, P: CurveProjective, B: AsRef<[u8]>>(
+    pks: &[P],
+    msgs: &'a [B],
+) -> (Vec<&'a [u8]>, Vec<P>) {
+    let mut first = HashMap::<&[u8], usize>::with_capacity(msgs.len());
+    let mut grouped_msgs = Vec::with_capacity(msgs.len());
+    let mut grouped_pks: Vec<P> = Vec::with_capacity(pks.len());
+    for (pk, msg) in pks.iter().zip(msgs) {
+        match first.entry(msg.as_ref()) {
+            Entry::Occupied(e) => grouped_pks[*e.get()].add_assign(pk),
+            Entry::Vacant(e) => {
+                e.insert(grouped_msgs.len());
+                grouped_msgs.push(msg.as_ref());
+                grouped_pks.push(*pk);
+            }
+        }
+    }
+    (grouped_msgs, grouped_pks)
+}
+
+// like _group_by_message, for messages already hashed to the curve
+fn _group_by_point<G: CurveProjective, P: CurveProjective>(
+    pks: &[P],
+    hashes: &[G],
+) -> (Vec<G>, Vec<P>) {
+    let mut first = HashMap::<Vec<u8>, usize>::with_capacity(hashes.len());
+    let mut grouped_hashes = Vec::with_capacity(hashes.len());
+    let mut grouped_pks: Vec<P> = Vec::with_capacity(pks.len());
+    for (pk, hash) in pks.iter().zip(hashes) {
+        match first.entry(_point_key(hash)) {
+            Entry::Occupied(e) => grouped_pks[*e.get()].add_assign(pk),
+            Entry::Vacant(e) => {
+                e.insert(grouped_hashes.len());
+                grouped_hashes.push(*hash);
+                grouped_pks.push(*pk);
+            }
+        }
+    }
+    (grouped_hashes, grouped_pks)
+}
+
+// a point's compressed encoding, for comparing hashed messages
+fn _point_key<G: CurveProjective>(p: &G) -> Vec<u8> {
+    p.into_affine().into_compressed().as_ref().to_vec()
+}
+
+// KeyValidate helper: used in aggregate and multisig verification
+pub(crate) fn _keys_validate<T: BLSSigCore<X>, X: ExpandMsg>(
+    pks: &[T::PKType],
+) -> Result<(), BlsError> {
+    for pk in pks {
+        T::try_key_validate(pk)?;
+    }
+    Ok(())
+}
+
+// The scheme-specific parts of signing and verification, with the ciphersuite as a parameter.
+// The scheme traits call these with their CSUITE; Scheme's *_with_dst methods, with a custom DST.
+
+// Basic AggregateVerify: enforce uniqueness of messages, then invoke core (skips KeyValidate)
+pub(crate) fn _basic_aggregate_verify<T: BLSSigCore<X>, X: ExpandMsg, B: AsRef<[u8]>>(
+    pks: &[T::PKType],
+    msgs: &[B],
+    sig: T,
+    dst: &[u8],
+) -> Result<(), BlsError> {
+    _check_agg_inputs(pks.len(), msgs.len())?;
+
+    // enforce uniqueness of messages
+    let mut msg_set = HashSet::<&[u8]>::with_capacity(msgs.len());
+    for msg in msgs {
+        msg_set.insert(msg.as_ref());
+    }
+    if msg_set.len() != msgs.len() {
+        return Err(BlsError::DuplicateMessage);
+    }
+
+    T::try_core_aggregate_verify(pks, msgs, sig, dst)
+}
+
+// Basic AggregateVerify on messages already hashed to the curve: the hashes must be distinct
+pub(crate) fn _basic_aggregate_verify_hashed<T: BLSSigCore<X>, X: ExpandMsg>(
+    pks: &[T::PKType],
+    hashes: &[T],
+    sig: T,
+) -> Result<(), BlsError> {
+    _check_agg_inputs(pks.len(), hashes.len())?;
+
+    // enforce uniqueness of messages
+    let hash_set: HashSet<Vec<u8>> = hashes.iter().map(_point_key).collect();
+    if hash_set.len() != hashes.len() {
+        return Err(BlsError::DuplicateMessage);
+    }
+
+    T::try_core_aggregate_verify_hashed(pks, hashes, sig)
+}
+
+// prepend pk to msg, for the message augmentation scheme
+pub(crate) fn _augment<T: BLSSignatureAug<X>, X: ExpandMsg, B: AsRef<[u8]>>(
+    pk: &T::PKType,
+    msg: B,
+) -> Vec<u8> {
+    let mut pk_msg_vec = T::pk_bytes(pk, msg.as_ref().len());
+    pk_msg_vec.extend_from_slice(msg.as_ref());
+    pk_msg_vec
+}
+
+fn _augment_all<T: BLSSignatureAug<X>, X: ExpandMsg, B: AsRef<[u8]>>(
+    pks: &[T::PKType],
+    msgs: &[B],
+) -> Vec<Vec<u8>> {
+    msgs.iter()
+        .zip(pks)
+        .map(|(msg, pk)| _augment::<T, X, _>(pk, msg))
+        .collect()
+}
+
+// Aug Sign: augment message and then invoke core
+pub(crate) fn _aug_sign<T: BLSSignatureAug<X>, X: ExpandMsg, B: AsRef<[u8]>>(
+    x_prime: &ScalarT<T>,
+    msg: B,
+    dst: &[u8],
+) -> T {
+    let pk = {
+        let mut tmp = <T::PKType as CurveProjective>::one();
+        ct_mul_assign(&mut tmp, x_prime);
+        tmp
+    };
+    T::core_sign(*x_prime, _augment::<T, X, _>(&pk, msg), dst)
+}
+
+// Aug Verify: augment message and then invoke core (skips KeyValidate)
+pub(crate) fn _aug_verify<T: BLSSignatureAug<X>, X: ExpandMsg, B: AsRef<[u8]>>(
+    pk: T::PKType,
+    sig: T,
+    msg: B,
+    dst: &[u8],
+) -> Result<(), BlsError> {
+    T::try_core_verify(pk, sig, _augment::<T, X, _>(&pk, msg), dst)
+}
+
+// Aug AggregateVerify: augment all messages and then invoke core (skips KeyValidate)
+pub(crate) fn _aug_aggregate_verify<T: BLSSignatureAug<X>, X: ExpandMsg, B: AsRef<[u8]>>(
+    pks: &[T::PKType],
+    msgs: &[B],
+    sig: T,
+    dst: &[u8],
+) -> Result<(), BlsError> {
+    _check_agg_inputs(pks.len(), msgs.len())?;
+    T::try_core_aggregate_verify(pks, &_augment_all::<T, X, _>(pks, msgs)[..], sig, dst)
+}
+
+// PopProve: sign the serialized public key under the PoP ciphersuite
+pub(crate) fn _pop_prove<T: BLSSigCore<X>, X: ExpandMsg>(
+    x_prime: &ScalarT<T>,
+    pk: &T::PKType,
+    dst: &[u8],
+) -> T {
+    T::core_sign(*x_prime, pk.into_affine().into_compressed(), dst)
+}
+
+// PopVerify (skips KeyValidate)
+pub(crate) fn _pop_verify<T: BLSSigCore<X>, X: ExpandMsg>(
+    pk: T::PKType,
+    sig: T,
+    dst: &[u8],
+) -> Result<(), BlsError> {
+    let pk_bytes = pk.into_affine().into_compressed();
+    T::try_core_verify(pk, sig, pk_bytes, dst)
+}
+
 /// BLS signature implementation
-pub trait BLSSigCore<X: ExpandMsg>: CurveProjective {
+pub trait BLSSigCore<X: ExpandMsg>: CurveProjective + PointEncoding + CtSelect {
     /// The type of the public key
     type PKType: CurveProjective<Engine = <Self as CurveProjective>::Engine, Scalar = ScalarT<Self>>
-        + SerDes;
+        + SerDes
+        + PointEncoding
+        + CtSelect;
 
     /// Generate secret exponent and public key
     /// * input: the secret key as bytes
@@ -56,16 +390,54 @@ pub trait BLSSigCore<X: ExpandMsg>: CurveProjective {
     /// * output: the public key g^x_prime
     fn keygen<B: AsRef<[u8]>>(sk: B) -> (ScalarT<Self>, Self::PKType);
 
+    /// Generate secret exponent and public key following the current draft's KeyGen
+    /// * input: the input keying material, at least 32 bytes
+    /// * input: the key_info string, possibly empty
+    /// * output: None if the IKM is too short, else the secret scalar and the public key
+    fn keygen_with_info<B: AsRef<[u8]>, I: AsRef<[u8]>>(
+        ikm: B,
+        key_info: I,
+    ) -> Option<(ScalarT<Self>, Self::PKType)>;
+
+    /// Validate a public key (KeyValidate)
+    /// * input: public key, a group element
+    /// * output: false if the key is the identity or is not in the prime-order subgroup
+    fn key_validate(pk: &Self::PKType) -> bool {
+        Self::try_key_validate(pk).is_ok()
+    }
+
+    /// like key_validate, but returns the reason for rejection
+    fn try_key_validate(pk: &Self::PKType) -> Result<(), BlsError>;
+
+    /// Hash a message to the signature group (hash_to_point)
+    /// * input: the message as bytes
+    /// * input: the ciphersuite ID
+    /// * output: the point that core_sign multiplies by the secret scalar
+    fn hash_message<B: AsRef<[u8]>, C: AsRef<[u8]>>(msg: B, ciphersuite: C) -> Self;
+
     /// Sign a message
     /// * input: the actual secret key x_prime
     /// * input: the message as bytes
     /// * input: the ciphersuite ID
     /// * output: a signature
+    ///
+    /// The multiplication by x_prime takes the same sequence of group operations for every
+    /// scalar; see the timing notes in the README.
     fn core_sign<B: AsRef<[u8]>, C: AsRef<[u8]>>(
         x_prime: ScalarT<Self>,
         msg: B,
         ciphersuite: C,
-    ) -> Self;
+    ) -> Self {
+        Self::core_sign_hashed(x_prime, Self::hash_message(msg, ciphersuite))
+    }
+
+    /// like core_sign, but with a message already hashed by hash_message
+    fn core_sign_hashed(mut x_prime: ScalarT<Self>, hash: Self) -> Self {
+        let mut p = hash;
+        ct_mul_assign(&mut p, &x_prime);
+        _wipe_scalar(&mut x_prime);
+        p
+    }
 
     /// Verify a signature
     /// * input: public key, a group element
@@ -78,7 +450,62 @@ pub trait BLSSigCore<X: ExpandMsg>: CurveProjective {
         sig: Self,
         msg: B,
         ciphersuite: C,
-    ) -> bool;
+    ) -> bool {
+        Self::try_core_verify(pk, sig, msg, ciphersuite).is_ok()
+    }
+
+    /// like core_verify, but returns the reason for rejection
+    fn try_core_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+        pk: Self::PKType,
+        sig: Self,
+        msg: B,
+        ciphersuite: C,
+    ) -> Result<(), BlsError> {
+        Self::try_core_verify_hashed(pk, sig, Self::hash_message(msg, ciphersuite))
+    }
+
+    /// like core_verify, but with a message already hashed by hash_message
+    fn core_verify_hashed(pk: Self::PKType, sig: Self, hash: Self) -> bool {
+        Self::try_core_verify_hashed(pk, sig, hash).is_ok()
+    }
+
+    /// like core_verify_hashed, but returns the reason for rejection
+    fn try_core_verify_hashed(pk: Self::PKType, sig: Self, hash: Self) -> Result<(), BlsError> {
+        Self::try_core_verify_prepared_hashed(&pk.into_affine().prepare(), sig, hash)
+    }
+
+    /// like core_verify, but with a public key that was already prepared for the pairing,
+    /// so that verifying repeatedly against the same key skips that work
+    fn core_verify_prepared<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+        pk: &PKPrepared<Self, X>,
+        sig: Self,
+        msg: B,
+        ciphersuite: C,
+    ) -> bool {
+        Self::try_core_verify_prepared(pk, sig, msg, ciphersuite).is_ok()
+    }
+
+    /// like core_verify_prepared, but returns the reason for rejection
+    fn try_core_verify_prepared<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+        pk: &PKPrepared<Self, X>,
+        sig: Self,
+        msg: B,
+        ciphersuite: C,
+    ) -> Result<(), BlsError> {
+        Self::try_core_verify_prepared_hashed(pk, sig, Self::hash_message(msg, ciphersuite))
+    }
+
+    /// like core_verify_prepared, but with a message already hashed by hash_message
+    fn core_verify_prepared_hashed(pk: &PKPrepared<Self, X>, sig: Self, hash: Self) -> bool {
+        Self::try_core_verify_prepared_hashed(pk, sig, hash).is_ok()
+    }
+
+    /// like core_verify_prepared_hashed, but returns the reason for rejection
+    fn try_core_verify_prepared_hashed(
+        pk: &PKPrepared<Self, X>,
+        sig: Self,
+        hash: Self,
+    ) -> Result<(), BlsError>;
 
     /// Aggregate signatures
     fn aggregate(sigs: &[Self]) -> Self {
@@ -86,12 +513,104 @@ pub trait BLSSigCore<X: ExpandMsg>: CurveProjective {
     }
 
     /// Verify an aggregated signature
+    /// * fails if the numbers of public keys and messages differ, or if there are none
+    /// * equal messages are hashed and paired once, against the sum of their public keys
     fn core_aggregate_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
         pks: &[Self::PKType],
         msgs: &[B],
         sig: Self,
         ciphersuite: C,
-    ) -> bool;
+    ) -> bool {
+        Self::try_core_aggregate_verify(pks, msgs, sig, ciphersuite).is_ok()
+    }
+
+    /// like core_aggregate_verify, but returns the reason for rejection
+    fn try_core_aggregate_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+        ciphersuite: C,
+    ) -> Result<(), BlsError>;
+
+    /// like core_aggregate_verify, but with messages already hashed by hash_message
+    fn core_aggregate_verify_hashed(pks: &[Self::PKType], hashes: &[Self], sig: Self) -> bool {
+        Self::try_core_aggregate_verify_hashed(pks, hashes, sig).is_ok()
+    }
+
+    /// like core_aggregate_verify_hashed, but returns the reason for rejection
+    fn try_core_aggregate_verify_hashed(
+        pks: &[Self::PKType],
+        hashes: &[Self],
+        sig: Self,
+    ) -> Result<(), BlsError>;
+
+    /// Verify many independent signatures at once
+    /// * input: public keys, messages, and signatures, one of each per signer
+    /// * input: ciphersuite ID
+    /// * input: a source of randomness for the 128-bit randomizers
+    /// * output: true only if every signature is valid (except with probability about 2^-128)
+    fn core_batch_verify<B: AsRef<[u8]>, C: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        ciphersuite: C,
+        rng: &mut R,
+    ) -> bool {
+        Self::try_core_batch_verify(pks, msgs, sigs, ciphersuite, rng).is_ok()
+    }
+
+    /// like core_batch_verify, but returns the reason for rejection
+    fn try_core_batch_verify<B: AsRef<[u8]>, C: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        ciphersuite: C,
+        rng: &mut R,
+    ) -> Result<(), BlsError>;
+
+    /// like core_aggregate_verify, but hashes the messages and runs the Miller loop on one
+    /// thread per CPU
+    #[cfg(feature = "parallel")]
+    fn core_aggregate_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+        ciphersuite: C,
+    ) -> bool {
+        Self::try_core_aggregate_verify_parallel(pks, msgs, sig, ciphersuite).is_ok()
+    }
+
+    /// like core_aggregate_verify_parallel, but returns the reason for rejection
+    #[cfg(feature = "parallel")]
+    fn try_core_aggregate_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+        ciphersuite: C,
+    ) -> Result<(), BlsError>;
+
+    /// like core_batch_verify, but hashes the messages and runs the Miller loop on one
+    /// thread per CPU
+    #[cfg(feature = "parallel")]
+    fn core_batch_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        ciphersuite: C,
+        rng: &mut R,
+    ) -> bool {
+        Self::try_core_batch_verify_parallel(pks, msgs, sigs, ciphersuite, rng).is_ok()
+    }
+
+    /// like core_batch_verify_parallel, but returns the reason for rejection
+    #[cfg(feature = "parallel")]
+    fn try_core_batch_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        ciphersuite: C,
+        rng: &mut R,
+    ) -> Result<(), BlsError>;
 }
 
 /// 'Basic' BLS signature
@@ -104,23 +623,85 @@ pub trait BLSSignatureBasic<X: ExpandMsg>: BLSSigCore<X> {
         <Self as BLSSigCore<X>>::core_sign(x_prime, msg, Self::CSUITE)
     }
 
-    /// re-export from BLSSigCore
+    /// validate pk, then invoke verify from BLSSigCore
     fn verify<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> bool {
-        <Self as BLSSigCore<X>>::core_verify(pk, sig, msg, Self::CSUITE)
+        Self::try_verify(pk, sig, msg).is_ok()
+    }
+
+    /// like verify, but returns the reason for rejection
+    fn try_verify<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_key_validate(&pk)?;
+        Self::try_verify_prevalidated(pk, sig, msg)
+    }
+
+    /// like verify, but skips KeyValidate: only for keys that were already validated
+    fn verify_prevalidated<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> bool {
+        Self::try_verify_prevalidated(pk, sig, msg).is_ok()
+    }
+
+    /// like verify_prevalidated, but returns the reason for rejection
+    fn try_verify_prevalidated<B: AsRef<[u8]>>(
+        pk: Self::PKType,
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_core_verify(pk, sig, msg, Self::CSUITE)
     }
 
-    /// check for uniqueness of msgs, then invoke verify from BLSSigCore
+    /// validate pks, then invoke aggregate_verify_prevalidated
     fn aggregate_verify<B: AsRef<[u8]>>(pks: &[Self::PKType], msgs: &[B], sig: Self) -> bool {
-        // enforce uniqueness of messages
-        let mut msg_set = HashSet::<&[u8]>::with_capacity(msgs.len());
-        for msg in msgs {
-            msg_set.insert(msg.as_ref());
-        }
-        if msg_set.len() != msgs.len() {
-            return false;
-        }
+        Self::try_aggregate_verify(pks, msgs, sig).is_ok()
+    }
+
+    /// like aggregate_verify, but returns the reason for rejection
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        _keys_validate::<Self, X>(pks)?;
+        Self::try_aggregate_verify_prevalidated(pks, msgs, sig)
+    }
+
+    /// check for uniqueness of msgs, then invoke verify from BLSSigCore (skips KeyValidate)
+    fn aggregate_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> bool {
+        Self::try_aggregate_verify_prevalidated(pks, msgs, sig).is_ok()
+    }
+
+    /// like aggregate_verify_prevalidated, but returns the reason for rejection
+    fn try_aggregate_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        _basic_aggregate_verify(pks, msgs, sig, Self::CSUITE)
+    }
 
-        <Self as BLSSigCore<X>>::core_aggregate_verify(pks, msgs, sig, Self::CSUITE)
+    /// validate pks, then verify many independent signatures at once
+    fn batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> bool {
+        Self::try_batch_verify(pks, msgs, sigs, rng).is_ok()
+    }
+
+    /// like batch_verify, but returns the reason for rejection
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        _keys_validate::<Self, X>(pks)?;
+        <Self as BLSSigCore<X>>::try_core_batch_verify(pks, msgs, sigs, Self::CSUITE, rng)
     }
 }
 
@@ -134,40 +715,105 @@ pub trait BLSSignatureAug<X: ExpandMsg>: BLSSigCore<X> {
 
     /// turn a public key into a vector
     fn pk_bytes(pk: &Self::PKType, size_hint: usize) -> Vec<u8> {
-        // 96 bytes of overhead for the PK, plus the size hint
-        let mut cur = Cursor::new(Vec::<u8>::with_capacity(size_hint + Self::PK_LEN));
-        assert!(pk.serialize(&mut cur, true).is_ok());
-        cur.into_inner()
+        // PK_LEN bytes of overhead for the PK, plus the size hint
+        let mut ret = Vec::<u8>::with_capacity(size_hint + Self::PK_LEN);
+        ret.extend_from_slice(pk.into_affine().into_compressed().as_ref());
+        ret
     }
 
     /// augment message and then invoke coresign
-    fn sign<B: AsRef<[u8]>>(x_prime: ScalarT<Self>, msg: B) -> Self {
-        let pk = {
-            let mut tmp = <Self::PKType as CurveProjective>::one();
-            tmp.mul_assign(x_prime);
-            tmp
-        };
-        let mut pk_msg_vec = Self::pk_bytes(&pk, msg.as_ref().len());
-        pk_msg_vec.extend_from_slice(msg.as_ref());
-        <Self as BLSSigCore<X>>::core_sign(x_prime, &pk_msg_vec, Self::CSUITE)
+    fn sign<B: AsRef<[u8]>>(mut x_prime: ScalarT<Self>, msg: B) -> Self {
+        let sig = _aug_sign::<Self, X, _>(&x_prime, msg, Self::CSUITE);
+        _wipe_scalar(&mut x_prime);
+        sig
     }
 
-    /// augment message and then invoke coreverify
+    /// validate pk, then invoke verify_prevalidated
     fn verify<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> bool {
-        let mut pk_msg_vec = Self::pk_bytes(&pk, msg.as_ref().len());
-        pk_msg_vec.extend_from_slice(msg.as_ref());
-        <Self as BLSSigCore<X>>::core_verify(pk, sig, &pk_msg_vec, Self::CSUITE)
+        Self::try_verify(pk, sig, msg).is_ok()
+    }
+
+    /// like verify, but returns the reason for rejection
+    fn try_verify<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_key_validate(&pk)?;
+        Self::try_verify_prevalidated(pk, sig, msg)
+    }
+
+    /// augment message and then invoke coreverify (skips KeyValidate)
+    fn verify_prevalidated<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> bool {
+        Self::try_verify_prevalidated(pk, sig, msg).is_ok()
     }
 
-    /// augment all messages and then invoke coreverify
+    /// like verify_prevalidated, but returns the reason for rejection
+    fn try_verify_prevalidated<B: AsRef<[u8]>>(
+        pk: Self::PKType,
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        _aug_verify::<Self, X, _>(pk, sig, msg, Self::CSUITE)
+    }
+
+    /// validate pks, then invoke aggregate_verify_prevalidated
     fn aggregate_verify<B: AsRef<[u8]>>(pks: &[Self::PKType], msgs: &[B], sig: Self) -> bool {
-        let mut pks_msgs_vec = Vec::<Vec<u8>>::with_capacity(msgs.len());
-        for (msg, pk) in msgs.iter().zip(pks) {
-            let mut pk_msg_vec = Self::pk_bytes(&pk, msg.as_ref().len());
-            pk_msg_vec.extend_from_slice(msg.as_ref());
-            pks_msgs_vec.push(pk_msg_vec);
-        }
-        <Self as BLSSigCore<X>>::core_aggregate_verify(pks, &pks_msgs_vec[..], sig, Self::CSUITE)
+        Self::try_aggregate_verify(pks, msgs, sig).is_ok()
+    }
+
+    /// like aggregate_verify, but returns the reason for rejection
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        _keys_validate::<Self, X>(pks)?;
+        Self::try_aggregate_verify_prevalidated(pks, msgs, sig)
+    }
+
+    /// augment all messages and then invoke coreverify (skips KeyValidate)
+    fn aggregate_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> bool {
+        Self::try_aggregate_verify_prevalidated(pks, msgs, sig).is_ok()
+    }
+
+    /// like aggregate_verify_prevalidated, but returns the reason for rejection
+    fn try_aggregate_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        _aug_aggregate_verify::<Self, X, _>(pks, msgs, sig, Self::CSUITE)
+    }
+
+    /// validate pks, augment all messages, then verify many independent signatures at once
+    fn batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> bool {
+        Self::try_batch_verify(pks, msgs, sigs, rng).is_ok()
+    }
+
+    /// like batch_verify, but returns the reason for rejection
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        _keys_validate::<Self, X>(pks)?;
+
+        <Self as BLSSigCore<X>>::try_core_batch_verify(
+            pks,
+            &_augment_all::<Self, X, _>(pks, msgs)[..],
+            sigs,
+            Self::CSUITE,
+            rng,
+        )
     }
 }
 
@@ -187,43 +833,213 @@ pub trait BLSSignaturePop<X: ExpandMsg>: BLSSigCore<X> {
         <Self as BLSSigCore<X>>::core_sign(x_prime, msg, Self::CSUITE)
     }
 
-    /// re-export from BLSSigCore
+    /// validate pk, then invoke verify from BLSSigCore
     fn verify<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> bool {
-        <Self as BLSSigCore<X>>::core_verify(pk, sig, msg, Self::CSUITE)
+        Self::try_verify(pk, sig, msg).is_ok()
     }
 
-    /// just invoke verify from BLSSigCore
+    /// like verify, but returns the reason for rejection
+    fn try_verify<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_key_validate(&pk)?;
+        Self::try_verify_prevalidated(pk, sig, msg)
+    }
+
+    /// like verify, but skips KeyValidate: only for keys that were already validated
+    fn verify_prevalidated<B: AsRef<[u8]>>(pk: Self::PKType, sig: Self, msg: B) -> bool {
+        Self::try_verify_prevalidated(pk, sig, msg).is_ok()
+    }
+
+    /// like verify_prevalidated, but returns the reason for rejection
+    fn try_verify_prevalidated<B: AsRef<[u8]>>(
+        pk: Self::PKType,
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_core_verify(pk, sig, msg, Self::CSUITE)
+    }
+
+    /// validate pks, then invoke aggregate verify from BLSSigCore
     fn aggregate_verify<B: AsRef<[u8]>>(pks: &[Self::PKType], msgs: &[B], sig: Self) -> bool {
-        <Self as BLSSigCore<X>>::core_aggregate_verify(pks, msgs, sig, Self::CSUITE)
+        Self::try_aggregate_verify(pks, msgs, sig).is_ok()
+    }
+
+    /// like aggregate_verify, but returns the reason for rejection
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        _keys_validate::<Self, X>(pks)?;
+        Self::try_aggregate_verify_prevalidated(pks, msgs, sig)
+    }
+
+    /// just invoke aggregate verify from BLSSigCore (skips KeyValidate)
+    fn aggregate_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> bool {
+        Self::try_aggregate_verify_prevalidated(pks, msgs, sig).is_ok()
     }
 
-    /// verify a multisig
+    /// like aggregate_verify_prevalidated, but returns the reason for rejection
+    fn try_aggregate_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_core_aggregate_verify(pks, msgs, sig, Self::CSUITE)
+    }
+
+    /// validate pks, then verify a multisig
     fn multisig_verify<B: AsRef<[u8]>>(pks: &[Self::PKType], sig: Self, msg: B) -> bool {
-        let apk = _agg_help(pks);
-        <Self as BLSSigCore<X>>::core_verify(apk, sig, msg, Self::CSUITE)
+        Self::try_multisig_verify(pks, sig, msg).is_ok()
+    }
+
+    /// like multisig_verify, but returns the reason for rejection
+    fn try_multisig_verify<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        _keys_validate::<Self, X>(pks)?;
+        Self::try_multisig_verify_prevalidated(pks, sig, msg)
+    }
+
+    /// verify a multisig (skips KeyValidate, e.g., for keys registered with a PoP)
+    fn multisig_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        sig: Self,
+        msg: B,
+    ) -> bool {
+        Self::try_multisig_verify_prevalidated(pks, sig, msg).is_ok()
+    }
+
+    /// like multisig_verify_prevalidated, but returns the reason for rejection
+    fn try_multisig_verify_prevalidated<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        let apk = Self::try_aggregate_public_keys_prevalidated(pks)?;
+        Self::try_fast_aggregate_verify_apk(apk, sig, msg)
+    }
+
+    /// validate pks, then aggregate them for use with fast_aggregate_verify_apk
+    fn aggregate_public_keys(pks: &[Self::PKType]) -> Option<Self::PKType> {
+        Self::try_aggregate_public_keys(pks).ok()
+    }
+
+    /// like aggregate_public_keys, but returns the reason for rejection
+    fn try_aggregate_public_keys(pks: &[Self::PKType]) -> Result<Self::PKType, BlsError> {
+        _keys_validate::<Self, X>(pks)?;
+        Self::try_aggregate_public_keys_prevalidated(pks)
+    }
+
+    /// aggregate pks (skips KeyValidate)
+    fn aggregate_public_keys_prevalidated(pks: &[Self::PKType]) -> Option<Self::PKType> {
+        Self::try_aggregate_public_keys_prevalidated(pks).ok()
+    }
+
+    /// like aggregate_public_keys_prevalidated, but returns the reason for rejection
+    fn try_aggregate_public_keys_prevalidated(
+        pks: &[Self::PKType],
+    ) -> Result<Self::PKType, BlsError> {
+        if pks.is_empty() {
+            return Err(BlsError::EmptyInput);
+        }
+        Ok(_agg_help(pks))
+    }
+
+    /// FastAggregateVerify from the draft: validate pks, then verify a multisig
+    fn fast_aggregate_verify<B: AsRef<[u8]>>(pks: &[Self::PKType], sig: Self, msg: B) -> bool {
+        Self::try_fast_aggregate_verify(pks, sig, msg).is_ok()
+    }
+
+    /// like fast_aggregate_verify, but returns the reason for rejection
+    fn try_fast_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[Self::PKType],
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        Self::try_multisig_verify(pks, sig, msg)
+    }
+
+    /// FastAggregateVerify given the output of aggregate_public_keys, which must not be
+    /// computed from keys that skipped KeyValidate or PoP verification
+    fn fast_aggregate_verify_apk<B: AsRef<[u8]>>(apk: Self::PKType, sig: Self, msg: B) -> bool {
+        Self::try_fast_aggregate_verify_apk(apk, sig, msg).is_ok()
+    }
+
+    /// like fast_aggregate_verify_apk, but returns the reason for rejection
+    fn try_fast_aggregate_verify_apk<B: AsRef<[u8]>>(
+        apk: Self::PKType,
+        sig: Self,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_core_verify(apk, sig, msg, Self::CSUITE)
+    }
+
+    /// validate pks, then verify many independent signatures at once
+    fn batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> bool {
+        Self::try_batch_verify(pks, msgs, sigs, rng).is_ok()
+    }
+
+    /// like batch_verify, but returns the reason for rejection
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[Self::PKType],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        _keys_validate::<Self, X>(pks)?;
+        <Self as BLSSigCore<X>>::try_core_batch_verify(pks, msgs, sigs, Self::CSUITE, rng)
     }
 
     /// prove possession
     fn pop_prove<B: AsRef<[u8]>>(sk: B) -> Self {
-        let (x_prime, pk) = <Self as BLSSigCore<X>>::keygen(sk);
-        let pk_bytes = {
-            let mut buf = GenericArray::<u8, Self::Length>::default();
-            let mut cur = Cursor::new(&mut buf[..]);
-            assert!(pk.serialize(&mut cur, true).is_ok());
-            buf
-        };
-        <Self as BLSSigCore<X>>::core_sign(x_prime, &pk_bytes[..], Self::CSUITE_POP)
+        let (mut x_prime, pk) = <Self as BLSSigCore<X>>::keygen(sk);
+        let sig = _pop_prove::<Self, X>(&x_prime, &pk, Self::CSUITE_POP);
+        _wipe_scalar(&mut x_prime);
+        sig
+    }
+
+    /// prove possession, given the secret exponent and the matching public key
+    fn pop_prove_with(mut x_prime: ScalarT<Self>, pk: &<Self as BLSSigCore<X>>::PKType) -> Self {
+        let sig = _pop_prove::<Self, X>(&x_prime, pk, Self::CSUITE_POP);
+        _wipe_scalar(&mut x_prime);
+        sig
     }
 
-    /// check proof of possession
+    /// validate pk, then check proof of possession
     fn pop_verify(pk: <Self as BLSSigCore<X>>::PKType, sig: Self) -> bool {
-        let pk_bytes = {
-            let mut buf = GenericArray::<u8, Self::Length>::default();
-            let mut cur = Cursor::new(&mut buf[..]);
-            assert!(pk.serialize(&mut cur, true).is_ok());
-            buf
-        };
-        <Self as BLSSigCore<X>>::core_verify(pk, sig, &pk_bytes[..], Self::CSUITE_POP)
+        Self::try_pop_verify(pk, sig).is_ok()
+    }
+
+    /// like pop_verify, but returns the reason for rejection
+    fn try_pop_verify(pk: <Self as BLSSigCore<X>>::PKType, sig: Self) -> Result<(), BlsError> {
+        <Self as BLSSigCore<X>>::try_key_validate(&pk)?;
+        Self::try_pop_verify_prevalidated(pk, sig)
+    }
+
+    /// check proof of possession (skips KeyValidate)
+    fn pop_verify_prevalidated(pk: <Self as BLSSigCore<X>>::PKType, sig: Self) -> bool {
+        Self::try_pop_verify_prevalidated(pk, sig).is_ok()
+    }
+
+    /// like pop_verify_prevalidated, but returns the reason for rejection
+    fn try_pop_verify_prevalidated(
+        pk: <Self as BLSSigCore<X>>::PKType,
+        sig: Self,
+    ) -> Result<(), BlsError> {
+        _pop_verify::<Self, X>(pk, sig, Self::CSUITE_POP)
     }
 }
 
@@ -233,59 +1049,99 @@ impl<X: ExpandMsg> BLSSigCore<X> for G1 {
     fn keygen<B: AsRef<[u8]>>(sk: B) -> (Fr, G2) {
         let x_prime = xprime_from_sk(sk);
         let mut pk = G2::one();
-        pk.mul_assign(x_prime);
+        ct_mul_assign(&mut pk, &x_prime);
         (x_prime, pk)
     }
 
-    fn core_sign<B: AsRef<[u8]>, C: AsRef<[u8]>>(x_prime: Fr, msg: B, ciphersuite: C) -> G1 {
-        let mut p = <G1 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite);
-        p.mul_assign(x_prime);
-        p
+    fn keygen_with_info<B: AsRef<[u8]>, I: AsRef<[u8]>>(ikm: B, key_info: I) -> Option<(Fr, G2)> {
+        let x_prime = xprime_from_ikm(ikm, key_info)?;
+        let mut pk = G2::one();
+        ct_mul_assign(&mut pk, &x_prime);
+        Some((x_prime, pk))
     }
 
-    fn core_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
-        pk: G2,
+    fn try_key_validate(pk: &G2) -> Result<(), BlsError> {
+        if pk.is_zero() {
+            Err(BlsError::IdentityKey)
+        } else if !pk.into_affine().in_subgroup() {
+            Err(BlsError::NotInSubgroup)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn hash_message<B: AsRef<[u8]>, C: AsRef<[u8]>>(msg: B, ciphersuite: C) -> G1 {
+        <G1 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
+    }
+
+    fn try_core_verify_prepared_hashed(pk: &G2Prepared, sig: G1, hash: G1) -> Result<(), BlsError> {
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
+        }
+        let p = hash.into_affine().prepare();
+        let g2gen = _neg_g2_prepared();
+        _pairing_check(&[(&p, pk), (&sig.into_affine().prepare(), g2gen)])
+    }
+
+    fn try_core_aggregate_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+        pks: &[G2],
+        msgs: &[B],
         sig: G1,
-        msg: B,
         ciphersuite: C,
-    ) -> bool {
-        let p = <G1 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
-            .into_affine()
-            .prepare();
-        let g2gen = {
-            let mut tmp = G2::one();
-            tmp.negate();
-            tmp.into_affine().prepare()
-        };
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
+        }
+        let (msgs, pks) = _group_by_message(pks, msgs);
+        let hashes: Vec<G1> = msgs
+            .iter()
+            .map(|msg| <G1 as HashToCurve<X>>::hash_to_curve(msg, &ciphersuite))
+            .collect();
+        _g1_aggregate_check(&hashes, &pks, sig)
+    }
 
-        match Bls12::final_exponentiation(&Bls12::miller_loop(&[
-            (&p, &pk.into_affine().prepare()),
-            (&sig.into_affine().prepare(), &g2gen),
-        ])) {
-            None => false,
-            Some(pairingproduct) => pairingproduct == Fq12::one(),
+    fn try_core_aggregate_verify_hashed(
+        pks: &[G2],
+        hashes: &[G1],
+        sig: G1,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), hashes.len())?;
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
         }
+        let (hashes, pks) = _group_by_point(pks, hashes);
+        _g1_aggregate_check(&hashes, &pks, sig)
     }
 
-    fn core_aggregate_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+    fn try_core_batch_verify<B: AsRef<[u8]>, C: AsRef<[u8]>, R: RngCore>(
         pks: &[G2],
         msgs: &[B],
-        sig: G1,
+        sigs: &[G1],
         ciphersuite: C,
-    ) -> bool {
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        // e(r_i * H(m_i), pk_i) for each i, and e(sum_i r_i * sig_i, -g2)
+        let mut sig_sum = G1::zero();
         let pvec = {
             let mut ret =
                 Vec::<<<G1 as CurveProjective>::Affine as CurveAffine>::Prepared>::with_capacity(
                     msgs.len() + 1,
                 );
-            for msg in msgs {
-                ret.push(
-                    <G1 as HashToCurve<X>>::hash_to_curve(msg, &ciphersuite)
-                        .into_affine()
-                        .prepare(),
-                );
+            for (msg, sig) in msgs.iter().zip(sigs) {
+                if !sig.into_affine().in_subgroup() {
+                    return Err(BlsError::NotInSubgroup);
+                }
+                let r = _batch_randomizer(rng);
+                let mut tmp = *sig;
+                tmp.mul_assign(r);
+                sig_sum.add_assign(&tmp);
+                let mut tmp = <G1 as HashToCurve<X>>::hash_to_curve(msg, &ciphersuite);
+                tmp.mul_assign(r);
+                ret.push(tmp.into_affine().prepare());
             }
-            ret.push(sig.into_affine().prepare());
+            ret.push(sig_sum.into_affine().prepare());
             ret
         };
         let qvec = {
@@ -296,34 +1152,94 @@ impl<X: ExpandMsg> BLSSigCore<X> for G1 {
             for pk in pks {
                 ret.push(pk.into_affine().prepare());
             }
-            let mut tmp = G2::one();
-            tmp.negate();
-            ret.push(tmp.into_affine().prepare());
+            ret.push(_neg_g2_prepared().clone());
             ret
         };
 
-        // XXX: this is annoying: miller_loop requires an iter to tuple refs, not tuples
         let pqz: Vec<_> = pvec.as_slice().iter().zip(qvec.as_slice()).collect();
-        match Bls12::final_exponentiation(&Bls12::miller_loop(&pqz[..])) {
-            None => false,
-            Some(pairingproduct) => pairingproduct == Fq12::one(),
-        }
+        _pairing_check(&pqz[..])
     }
-}
 
-impl BLSSignatureBasic<ExpandMsgXmd<Sha256>> for G1 {
-    const CSUITE: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
-}
-
-impl BLSSignatureAug<ExpandMsgXmd<Sha256>> for G1 {
-    const CSUITE: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_AUG_";
-    const PK_LEN: usize = 96;
-}
+    #[cfg(feature = "parallel")]
+    fn try_core_aggregate_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync>(
+        pks: &[G2],
+        msgs: &[B],
+        sig: G1,
+        ciphersuite: C,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
+        }
+        let ciphersuite = &ciphersuite;
+        let (msgs, pks) = _group_by_message(pks, msgs);
+        // e(H(m_i), pk_i) for each chunk of i
+        let partials = _par_chunks(msgs.len(), |range| {
+            let pvec: Vec<_> = msgs[range.clone()]
+                .iter()
+                .map(|msg| {
+                    <G1 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
+                        .into_affine()
+                        .prepare()
+                })
+                .collect();
+            let qvec: Vec<_> = pks[range]
+                .iter()
+                .map(|pk| pk.into_affine().prepare())
+                .collect();
+            let pqz: Vec<_> = pvec.iter().zip(qvec.iter()).collect();
+            Bls12::miller_loop(&pqz[..])
+        });
+        let g2gen = _neg_g2_prepared();
+        let f = Bls12::miller_loop(&[(&sig.into_affine().prepare(), g2gen)]);
+        _par_pairing_check(f, partials)
+    }
 
-impl BLSSignaturePop<ExpandMsgXmd<Sha256>> for G1 {
-    const CSUITE: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
-    const CSUITE_POP: &'static [u8] = b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
-    type Length = U96;
+    #[cfg(feature = "parallel")]
+    fn try_core_batch_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync, R: RngCore>(
+        pks: &[G2],
+        msgs: &[B],
+        sigs: &[G1],
+        ciphersuite: C,
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        // the rng stays on this thread
+        let rs: Vec<FrRepr> = (0..msgs.len()).map(|_| _batch_randomizer(rng)).collect();
+        let ciphersuite = &ciphersuite;
+        // e(r_i * H(m_i), pk_i) and sum_i r_i * sig_i for each chunk of i
+        let partials = _par_chunks(msgs.len(), |range| {
+            let mut sig_sum = G1::zero();
+            let mut pvec = Vec::with_capacity(range.len());
+            for i in range.clone() {
+                if !sigs[i].into_affine().in_subgroup() {
+                    return Err(BlsError::NotInSubgroup);
+                }
+                let mut tmp = sigs[i];
+                tmp.mul_assign(rs[i]);
+                sig_sum.add_assign(&tmp);
+                let mut tmp = <G1 as HashToCurve<X>>::hash_to_curve(&msgs[i], ciphersuite);
+                tmp.mul_assign(rs[i]);
+                pvec.push(tmp.into_affine().prepare());
+            }
+            let qvec: Vec<_> = pks[range]
+                .iter()
+                .map(|pk| pk.into_affine().prepare())
+                .collect();
+            let pqz: Vec<_> = pvec.iter().zip(qvec.iter()).collect();
+            Ok((Bls12::miller_loop(&pqz[..]), sig_sum))
+        });
+        let mut sig_sum = G1::zero();
+        let mut loops = Vec::with_capacity(partials.len());
+        for partial in partials {
+            let (f, sum) = partial?;
+            loops.push(f);
+            sig_sum.add_assign(&sum);
+        }
+        let g2gen = _neg_g2_prepared();
+        let f = Bls12::miller_loop(&[(&sig_sum.into_affine().prepare(), g2gen)]);
+        _par_pairing_check(f, loops)
+    }
 }
 
 impl<X: ExpandMsg> BLSSigCore<X> for G2 {
@@ -332,57 +1248,99 @@ impl<X: ExpandMsg> BLSSigCore<X> for G2 {
     fn keygen<B: AsRef<[u8]>>(sk: B) -> (Fr, G1) {
         let x_prime = xprime_from_sk(sk);
         let mut pk = G1::one();
-        pk.mul_assign(x_prime);
+        ct_mul_assign(&mut pk, &x_prime);
         (x_prime, pk)
     }
 
-    fn core_sign<B: AsRef<[u8]>, C: AsRef<[u8]>>(x_prime: Fr, msg: B, ciphersuite: C) -> G2 {
-        let mut p = <G2 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite);
-        p.mul_assign(x_prime);
-        p
+    fn keygen_with_info<B: AsRef<[u8]>, I: AsRef<[u8]>>(ikm: B, key_info: I) -> Option<(Fr, G1)> {
+        let x_prime = xprime_from_ikm(ikm, key_info)?;
+        let mut pk = G1::one();
+        ct_mul_assign(&mut pk, &x_prime);
+        Some((x_prime, pk))
     }
 
-    fn core_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
-        pk: G1,
+    fn try_key_validate(pk: &G1) -> Result<(), BlsError> {
+        if pk.is_zero() {
+            Err(BlsError::IdentityKey)
+        } else if !pk.into_affine().in_subgroup() {
+            Err(BlsError::NotInSubgroup)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn hash_message<B: AsRef<[u8]>, C: AsRef<[u8]>>(msg: B, ciphersuite: C) -> G2 {
+        <G2 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
+    }
+
+    fn try_core_verify_prepared_hashed(pk: &G1Prepared, sig: G2, hash: G2) -> Result<(), BlsError> {
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
+        }
+        let p = hash.into_affine().prepare();
+        let g1gen = _neg_g1_prepared();
+        _pairing_check(&[(pk, &p), (g1gen, &sig.into_affine().prepare())])
+    }
+
+    fn try_core_aggregate_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+        pks: &[G1],
+        msgs: &[B],
         sig: G2,
-        msg: B,
         ciphersuite: C,
-    ) -> bool {
-        let p = <G2 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
-            .into_affine()
-            .prepare();
-        let g1gen = {
-            let mut tmp = G1::one();
-            tmp.negate();
-            tmp.into_affine().prepare()
-        };
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
+        }
+        let (msgs, pks) = _group_by_message(pks, msgs);
+        let hashes: Vec<G2> = msgs
+            .iter()
+            .map(|msg| <G2 as HashToCurve<X>>::hash_to_curve(msg, &ciphersuite))
+            .collect();
+        _g2_aggregate_check(&hashes, &pks, sig)
+    }
 
-        match Bls12::final_exponentiation(&Bls12::miller_loop(&[
-            (&pk.into_affine().prepare(), &p),
-            (&g1gen, &sig.into_affine().prepare()),
-        ])) {
-            None => false,
-            Some(pairingproduct) => pairingproduct == Fq12::one(),
+    fn try_core_aggregate_verify_hashed(
+        pks: &[G1],
+        hashes: &[G2],
+        sig: G2,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), hashes.len())?;
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
         }
+        let (hashes, pks) = _group_by_point(pks, hashes);
+        _g2_aggregate_check(&hashes, &pks, sig)
     }
 
-    fn core_aggregate_verify<B: AsRef<[u8]>, C: AsRef<[u8]>>(
+    fn try_core_batch_verify<B: AsRef<[u8]>, C: AsRef<[u8]>, R: RngCore>(
         pks: &[G1],
         msgs: &[B],
-        sig: G2,
+        sigs: &[G2],
         ciphersuite: C,
-    ) -> bool {
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        // e(r_i * pk_i, H(m_i)) for each i, and e(-g1, sum_i r_i * sig_i)
+        let mut sig_sum = G2::zero();
         let pvec = {
             let mut ret =
                 Vec::<<<G1 as CurveProjective>::Affine as CurveAffine>::Prepared>::with_capacity(
                     pks.len() + 1,
                 );
-            for pk in pks {
-                ret.push(pk.into_affine().prepare());
+            for (pk, sig) in pks.iter().zip(sigs) {
+                if !sig.into_affine().in_subgroup() {
+                    return Err(BlsError::NotInSubgroup);
+                }
+                let r = _batch_randomizer(rng);
+                let mut tmp = *sig;
+                tmp.mul_assign(r);
+                sig_sum.add_assign(&tmp);
+                let mut tmp = *pk;
+                tmp.mul_assign(r);
+                ret.push(tmp.into_affine().prepare());
             }
-            let mut tmp = G1::one();
-            tmp.negate();
-            ret.push(tmp.into_affine().prepare());
+            ret.push(_neg_g1_prepared().clone());
             ret
         };
         let qvec = {
@@ -397,30 +1355,146 @@ impl<X: ExpandMsg> BLSSigCore<X> for G2 {
                         .prepare(),
                 );
             }
-            ret.push(sig.into_affine().prepare());
+            ret.push(sig_sum.into_affine().prepare());
             ret
         };
 
-        // XXX: this is annoying: miller_loop requires an iter to tuple refs, not tuples
         let pqz: Vec<_> = pvec.as_slice().iter().zip(qvec.as_slice()).collect();
-        match Bls12::final_exponentiation(&Bls12::miller_loop(&pqz[..])) {
-            None => false,
-            Some(pairingproduct) => pairingproduct == Fq12::one(),
+        _pairing_check(&pqz[..])
+    }
+
+    #[cfg(feature = "parallel")]
+    fn try_core_aggregate_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync>(
+        pks: &[G1],
+        msgs: &[B],
+        sig: G2,
+        ciphersuite: C,
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        if !sig.into_affine().in_subgroup() {
+            return Err(BlsError::NotInSubgroup);
         }
+        let ciphersuite = &ciphersuite;
+        let (msgs, pks) = _group_by_message(pks, msgs);
+        // e(pk_i, H(m_i)) for each chunk of i
+        let partials = _par_chunks(msgs.len(), |range| {
+            let pvec: Vec<_> = pks[range.clone()]
+                .iter()
+                .map(|pk| pk.into_affine().prepare())
+                .collect();
+            let qvec: Vec<_> = msgs[range]
+                .iter()
+                .map(|msg| {
+                    <G2 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
+                        .into_affine()
+                        .prepare()
+                })
+                .collect();
+            let pqz: Vec<_> = pvec.iter().zip(qvec.iter()).collect();
+            Bls12::miller_loop(&pqz[..])
+        });
+        let g1gen = _neg_g1_prepared();
+        let f = Bls12::miller_loop(&[(g1gen, &sig.into_affine().prepare())]);
+        _par_pairing_check(f, partials)
     }
-}
 
-impl BLSSignatureBasic<ExpandMsgXmd<Sha256>> for G2 {
-    const CSUITE: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+    #[cfg(feature = "parallel")]
+    fn try_core_batch_verify_parallel<B: AsRef<[u8]> + Sync, C: AsRef<[u8]> + Sync, R: RngCore>(
+        pks: &[G1],
+        msgs: &[B],
+        sigs: &[G2],
+        ciphersuite: C,
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        _check_batch_inputs(pks.len(), msgs.len(), sigs.len())?;
+        // the rng stays on this thread
+        let rs: Vec<FrRepr> = (0..msgs.len()).map(|_| _batch_randomizer(rng)).collect();
+        let ciphersuite = &ciphersuite;
+        // e(r_i * pk_i, H(m_i)) and sum_i r_i * sig_i for each chunk of i
+        let partials = _par_chunks(msgs.len(), |range| {
+            let mut sig_sum = G2::zero();
+            let mut pvec = Vec::with_capacity(range.len());
+            for i in range.clone() {
+                if !sigs[i].into_affine().in_subgroup() {
+                    return Err(BlsError::NotInSubgroup);
+                }
+                let mut tmp = sigs[i];
+                tmp.mul_assign(rs[i]);
+                sig_sum.add_assign(&tmp);
+                let mut tmp = pks[i];
+                tmp.mul_assign(rs[i]);
+                pvec.push(tmp.into_affine().prepare());
+            }
+            let qvec: Vec<_> = msgs[range]
+                .iter()
+                .map(|msg| {
+                    <G2 as HashToCurve<X>>::hash_to_curve(msg, ciphersuite)
+                        .into_affine()
+                        .prepare()
+                })
+                .collect();
+            let pqz: Vec<_> = pvec.iter().zip(qvec.iter()).collect();
+            Ok((Bls12::miller_loop(&pqz[..]), sig_sum))
+        });
+        let mut sig_sum = G2::zero();
+        let mut loops = Vec::with_capacity(partials.len());
+        for partial in partials {
+            let (f, sum) = partial?;
+            loops.push(f);
+            sig_sum.add_assign(&sum);
+        }
+        let g1gen = _neg_g1_prepared();
+        let f = Bls12::miller_loop(&[(g1gen, &sig_sum.into_affine().prepare())]);
+        _par_pairing_check(f, loops)
+    }
 }
 
-impl BLSSignatureAug<ExpandMsgXmd<Sha256>> for G2 {
-    const CSUITE: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_";
-    const PK_LEN: usize = 48;
-}
+// Implement the Basic, Aug, and Pop schemes in both groups for the expander $x. The ciphersuite
+// IDs are derived from $hash_id, the HASH_ID component of the hash-to-curve suite ID (e.g.,
+// "XMD:SHA-256"), so that adding a ciphersuite only takes one more invocation below.
+macro_rules! ciphersuites {
+    ($x:ty, $hash_id:literal) => {
+        impl BLSSignatureBasic<$x> for G1 {
+            const CSUITE: &'static [u8] =
+                concat!("BLS_SIG_BLS12381G1_", $hash_id, "_SSWU_RO_NUL_").as_bytes();
+        }
+
+        impl BLSSignatureAug<$x> for G1 {
+            const CSUITE: &'static [u8] =
+                concat!("BLS_SIG_BLS12381G1_", $hash_id, "_SSWU_RO_AUG_").as_bytes();
+            const PK_LEN: usize = 96;
+        }
+
+        impl BLSSignaturePop<$x> for G1 {
+            const CSUITE: &'static [u8] =
+                concat!("BLS_SIG_BLS12381G1_", $hash_id, "_SSWU_RO_POP_").as_bytes();
+            const CSUITE_POP: &'static [u8] =
+                concat!("BLS_POP_BLS12381G1_", $hash_id, "_SSWU_RO_POP_").as_bytes();
+            type Length = U96;
+        }
 
-impl BLSSignaturePop<ExpandMsgXmd<Sha256>> for G2 {
-    const CSUITE: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
-    const CSUITE_POP: &'static [u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
-    type Length = U48;
+        impl BLSSignatureBasic<$x> for G2 {
+            const CSUITE: &'static [u8] =
+                concat!("BLS_SIG_BLS12381G2_", $hash_id, "_SSWU_RO_NUL_").as_bytes();
+        }
+
+        impl BLSSignatureAug<$x> for G2 {
+            const CSUITE: &'static [u8] =
+                concat!("BLS_SIG_BLS12381G2_", $hash_id, "_SSWU_RO_AUG_").as_bytes();
+            const PK_LEN: usize = 48;
+        }
+
+        impl BLSSignaturePop<$x> for G2 {
+            const CSUITE: &'static [u8] =
+                concat!("BLS_SIG_BLS12381G2_", $hash_id, "_SSWU_RO_POP_").as_bytes();
+            const CSUITE_POP: &'static [u8] =
+                concat!("BLS_POP_BLS12381G2_", $hash_id, "_SSWU_RO_POP_").as_bytes();
+            type Length = U48;
+        }
+    };
 }
+
+ciphersuites!(ExpandMsgXmd<Sha256>, "XMD:SHA-256");
+ciphersuites!(ExpandMsgXmd<Sha512>, "XMD:SHA-512");
+ciphersuites!(ExpandMsgXof<Shake128>, "XOF:SHAKE128");
+ciphersuites!(ExpandMsgXof<Shake256>, "XOF:SHAKE256");
@@ -0,0 +1,263 @@
+/*!
+Hashing messages that arrive in pieces
+
+hash_to_curve reads the message exactly once, at the start of expand_message, so that a hash
+state can absorb the message a piece at a time, e.g., from a file too large to hold in memory.
+The expanded bytes then go through the rest of hash_to_curve as usual, and the resulting point
+is the one that the one-shot functions compute. Sign it with `SecretKey::sign_hashed` and
+verify with `Signature::verify_hashed`, or with `BLSSigCore::core_sign_hashed` and
+`BLSSigCore::core_verify_hashed` for a custom ciphersuite.
+
+Aug prepends the signer's public key to every message, so it has no `MessageStream`; at the
+core level, absorb `BLSSignatureAug::pk_bytes` before the message.
+*/
+
+use dst::{ExpandMsgDst, MAX_DST_LEN};
+use pairing_plus::bls12_381::{G1, G2};
+use pairing_plus::hash_to_curve::HashToCurve;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd, ExpandMsgXof};
+use pairing_plus::CurveProjective;
+use sha2::digest::generic_array::typenum::Unsigned;
+use sha2::digest::generic_array::GenericArray;
+use sha2::digest::{BlockInput, Digest, ExtendableOutput, Input};
+use sha2::Sha256;
+use signature::BLSSigCore;
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use types::{HashedMessage, PrehashScheme, SchemeMarker};
+
+/// A message expander that can absorb the message a piece at a time
+pub trait ExpandMsgStream: ExpandMsgDst {
+    /// The hash state after absorbing part of a message
+    type State: Clone;
+
+    /// The state before any of the message has been absorbed
+    fn start() -> Self::State;
+
+    /// Absorb the next piece of the message
+    fn absorb(state: &mut Self::State, data: &[u8]);
+
+    /// expand_message(msg, dst, len_in_bytes), where msg is everything absorbed into state
+    ///
+    /// A dst longer than MAX_DST_LEN bytes is first hashed with `ExpandMsgDst::oversize_dst`,
+    /// as a `CustomScheme` does.
+    fn finish(state: Self::State, dst: &[u8], len_in_bytes: usize) -> Vec<u8>;
+}
+
+impl<HashT: Digest + BlockInput + Clone> ExpandMsgStream for ExpandMsgXmd<HashT> {
+    type State = HashT;
+
+    fn start() -> HashT {
+        // b_0 starts with Z_pad, one block of zeros
+        HashT::new().chain(GenericArray::<u8, <HashT as BlockInput>::BlockSize>::default())
+    }
+
+    fn absorb(state: &mut HashT, data: &[u8]) {
+        state.input(data);
+    }
+
+    fn finish(state: HashT, dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+        let dst = &_short_dst::<Self>(dst)[..];
+        let b_in_bytes = <HashT as Digest>::OutputSize::to_usize();
+        let ell = len_in_bytes.div_ceil(b_in_bytes);
+        assert!(ell <= 255, "ell was too big in expand_message_xmd");
+        let b_0 = state
+            .chain([(len_in_bytes >> 8) as u8, len_in_bytes as u8, 0u8])
+            .chain(dst)
+            .chain([dst.len() as u8])
+            .result();
+
+        // b_1, then b_i = H((b_0 XOR b_(i - 1)) || i || DST_prime)
+        let mut b_vals = Vec::<u8>::with_capacity(ell * b_in_bytes);
+        let mut b_i = HashT::new()
+            .chain(&b_0[..])
+            .chain([1u8])
+            .chain(dst)
+            .chain([dst.len() as u8])
+            .result();
+        b_vals.extend_from_slice(&b_i[..]);
+        for idx in 1..ell {
+            for (b, b0) in b_i.iter_mut().zip(&b_0[..]) {
+                *b ^= b0;
+            }
+            b_i = HashT::new()
+                .chain(&b_i[..])
+                .chain([(idx + 1) as u8])
+                .chain(dst)
+                .chain([dst.len() as u8])
+                .result();
+            b_vals.extend_from_slice(&b_i[..]);
+        }
+        b_vals.truncate(len_in_bytes);
+        b_vals
+    }
+}
+
+impl<HashT: Default + ExtendableOutput + Input + Clone> ExpandMsgStream for ExpandMsgXof<HashT> {
+    type State = HashT;
+
+    fn start() -> HashT {
+        HashT::default()
+    }
+
+    fn absorb(state: &mut HashT, data: &[u8]) {
+        state.input(data);
+    }
+
+    fn finish(state: HashT, dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+        let dst = &_short_dst::<Self>(dst)[..];
+        state
+            .chain([(len_in_bytes >> 8) as u8, len_in_bytes as u8])
+            .chain(dst)
+            .chain([dst.len() as u8])
+            .vec_result(len_in_bytes)
+    }
+}
+
+// DST_prime ends with the DST's length as a single byte, so longer DSTs must be hashed first
+fn _short_dst<X: ExpandMsgDst>(dst: &[u8]) -> Cow<'_, [u8]> {
+    if dst.len() > MAX_DST_LEN {
+        Cow::Owned(X::oversize_dst(dst))
+    } else {
+        Cow::Borrowed(dst)
+    }
+}
+
+/// A group whose hash_to_curve can be finished from the output of expand_message
+pub trait FromUniformBytes: CurveProjective {
+    /// The number of bytes hash_to_curve asks expand_message for
+    const UNIFORM_LEN: usize;
+
+    /// The rest of hash_to_curve, given those bytes
+    fn from_uniform_bytes(bytes: &[u8]) -> Self;
+}
+
+// expand_message for bytes that were already expanded: hands them to hash_to_field unchanged
+struct Expanded;
+
+impl ExpandMsg for Expanded {
+    fn expand_message(msg: &[u8], _dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+        assert_eq!(msg.len(), len_in_bytes);
+        msg.to_vec()
+    }
+}
+
+impl FromUniformBytes for G1 {
+    // two elements of Fp, 64 bytes each
+    const UNIFORM_LEN: usize = 128;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> G1 {
+        <G1 as HashToCurve<Expanded>>::hash_to_curve(bytes, [])
+    }
+}
+
+impl FromUniformBytes for G2 {
+    // two elements of Fp2, 128 bytes each
+    const UNIFORM_LEN: usize = 256;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> G2 {
+        <G2 as HashToCurve<Expanded>>::hash_to_curve(bytes, [])
+    }
+}
+
+/// hash_message(msg, ciphersuite), where msg is everything absorbed into state
+pub fn hash_message_stream<G, X>(state: X::State, ciphersuite: &[u8]) -> G
+where
+    G: FromUniformBytes,
+    X: ExpandMsgStream,
+{
+    G::from_uniform_bytes(&X::finish(state, ciphersuite, G::UNIFORM_LEN)[..])
+}
+
+/// A message for scheme S, hashed as it is written, e.g., with `io::copy`
+pub struct MessageStream<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    X: ExpandMsgStream,
+{
+    state: X::State,
+    _scheme: SchemeMarker<G, S, X>,
+}
+
+impl<G, S, X: ExpandMsgStream> Clone for MessageStream<G, S, X> {
+    fn clone(&self) -> Self {
+        MessageStream {
+            state: self.state.clone(),
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<G, S, X: ExpandMsgStream> fmt::Debug for MessageStream<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the hash state is not Debug
+        f.write_str("MessageStream(..)")
+    }
+}
+
+impl<G, S, X> MessageStream<G, S, X>
+where
+    G: BLSSigCore<X> + FromUniformBytes,
+    S: PrehashScheme<G, X>,
+    X: ExpandMsgStream,
+{
+    /// Start hashing a message under S's ciphersuite
+    pub fn new() -> Self {
+        MessageStream {
+            state: X::start(),
+            _scheme: PhantomData,
+        }
+    }
+
+    /// Append data to the message
+    pub fn update<B: AsRef<[u8]>>(&mut self, data: B) {
+        X::absorb(&mut self.state, data.as_ref());
+    }
+
+    /// The hash of everything appended so far
+    pub fn finish(self) -> HashedMessage<G, S, X> {
+        HashedMessage::from_point(hash_message_stream::<G, X>(self.state, S::CSUITE))
+    }
+}
+
+impl<G, S, X> Default for MessageStream<G, S, X>
+where
+    G: BLSSigCore<X> + FromUniformBytes,
+    S: PrehashScheme<G, X>,
+    X: ExpandMsgStream,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G, S, X> Write for MessageStream<G, S, X>
+where
+    G: BLSSigCore<X> + FromUniformBytes,
+    S: PrehashScheme<G, X>,
+    X: ExpandMsgStream,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<G, S, X> HashedMessage<G, S, X>
+where
+    G: BLSSigCore<X> + FromUniformBytes,
+    S: PrehashScheme<G, X>,
+    X: ExpandMsgStream,
+{
+    /// Hash everything that reader yields, without holding it all in memory
+    pub fn from_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut stream = MessageStream::new();
+        io::copy(reader, &mut stream)?;
+        Ok(stream.finish())
+    }
+}
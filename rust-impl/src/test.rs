@@ -1,9 +1,35 @@
-use super::signature::{xprime_from_sk, BLSSigCore};
-use ff::PrimeField;
+use super::aggregator::Aggregator;
+use super::bitfield::Bitfield;
+use super::committee::Committee;
+use super::ct::{ct_mul_assign, CtSelect};
+use super::derive::{derive_child_sk, derive_master_sk, derive_path, parse_path};
+use super::dkg::{Commitment, Complaint, Dkg, EncryptedShare, Justification};
+use super::dst::{ExpandMsgDst, SchemeBuilder};
+use super::error::BlsError;
+use super::keystore::{normalize_password, Kdf, Keystore};
+use super::registry::{ciphersuite, ciphersuites};
+use super::signature::{
+    _wipe_scalar, xprime_from_ikm, xprime_from_sk, BLSSigCore, BLSSignatureAug, BLSSignatureBasic,
+    BLSSignaturePop,
+};
+use super::stream::{hash_message_stream, ExpandMsgStream, FromUniformBytes, MessageStream};
+use super::threshold::{split, PartialSignature};
+use super::types::{
+    Aug, Basic, HashedMessage, Pop, PrehashScheme, PreparedPublicKey, PublicKey, Scheme, SecretKey,
+    Signature,
+};
+use ff::{Field, PrimeField};
 use pairing_plus::bls12_381::{Fr, FrRepr, G1, G2};
-use pairing_plus::hash_to_field::ExpandMsgXmd;
-use pairing_plus::CurveProjective;
-use sha2::Sha256;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd, ExpandMsgXof};
+use pairing_plus::{CurveAffine, CurveProjective, EncodedPoint, SubgroupCheck};
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::{Shake128, Shake256};
+use std::collections::HashSet;
+use std::io::{self, Cursor};
+use std::mem::ManuallyDrop;
+use std::ptr;
 
 fn test_sig<T: CurveProjective + BLSSigCore<ExpandMsgXmd<Sha256>>>(ciphersuite: &[u8]) {
     let msg = "this is the message";
@@ -34,3 +60,1999 @@ fn test_xprime_from_sk() {
     ]);
     assert_eq!(fr_val, Fr::from_repr(expect).unwrap());
 }
+
+#[test]
+fn test_xprime_from_ikm() {
+    // EIP-2333 master key test vectors use this KeyGen with an empty key_info
+    let ikm = hex!("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04");
+    let expect = Fr::from_str(
+        "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+    );
+    assert_eq!(xprime_from_ikm(&ikm[..], b""), expect);
+
+    let ikm = hex!("3141592653589793238462643383279502884197169399375105820974944592");
+    let expect = Fr::from_str(
+        "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+    );
+    assert_eq!(xprime_from_ikm(&ikm[..], b""), expect);
+
+    // key_info is appended before the length
+    let expect = FrRepr([
+        0xddaefc28c8d8addeu64,
+        0x2fe401fb100a3aadu64,
+        0xd15bea7da0f78caeu64,
+        0x3d218eb181eb7e80u64,
+    ]);
+    assert_eq!(
+        xprime_from_ikm(&[0u8; 32][..], "key info"),
+        Some(Fr::from_repr(expect).unwrap())
+    );
+
+    // IKM must be at least 32 bytes
+    assert_eq!(xprime_from_ikm(&[0u8; 31][..], b""), None);
+}
+
+#[test]
+fn test_keygen_with_info() {
+    let ikm = [7u8; 32];
+    let (x_prime, pk) =
+        <G1 as BLSSigCore<ExpandMsgXmd<Sha256>>>::keygen_with_info(&ikm[..], "info").unwrap();
+    assert_eq!(Some(x_prime), xprime_from_ikm(&ikm[..], "info"));
+    let mut expect = G2::one();
+    expect.mul_assign(x_prime);
+    assert_eq!(pk, expect);
+    assert!(
+        <G2 as BLSSigCore<ExpandMsgXmd<Sha256>>>::keygen_with_info(&ikm[..31], "info").is_none()
+    );
+}
+
+fn test_typed<G, S>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+{
+    let msgs = ["message one", "message two", "message three"];
+    let sks: Vec<SecretKey<G, S>> = ["key one", "key two", "key three"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, S>> = sks.iter().map(SecretKey::public_key).collect();
+    let sigs: Vec<Signature<G, S>> = sks.iter().zip(&msgs).map(|(sk, m)| sk.sign(m)).collect();
+
+    for ((pk, sig), msg) in pks.iter().zip(&sigs).zip(&msgs) {
+        assert!(sig.verify(pk, msg));
+        assert!(pk.verify(msg, sig));
+        assert!(!sig.verify(pk, "some other message"));
+    }
+    assert!(!sigs[0].verify(&pks[1], msgs[0]));
+
+    let agg = Signature::aggregate(&sigs[..]);
+    assert!(agg.aggregate_verify(&pks[..], &msgs[..]));
+    assert!(!agg.aggregate_verify(&pks[..2], &msgs[..2]));
+}
+
+#[test]
+fn test_typed_g1() {
+    test_typed::<G1, Basic>();
+    test_typed::<G1, Aug>();
+    test_typed::<G1, Pop>();
+}
+
+#[test]
+fn test_typed_g2() {
+    test_typed::<G2, Basic>();
+    test_typed::<G2, Aug>();
+    test_typed::<G2, Pop>();
+}
+
+fn test_typed_pop<G: BLSSignaturePop<ExpandMsgXmd<Sha256>>>() {
+    let msg = "multisig message";
+    let sks: Vec<SecretKey<G, Pop>> = ["key one", "key two"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, Pop>> = sks.iter().map(SecretKey::public_key).collect();
+
+    // proofs of possession agree with the untyped API
+    let proof = sks[0].pop_prove();
+    assert_eq!(proof.point(), G::pop_prove("key one"));
+    assert!(pks[0].pop_verify(&proof));
+    assert!(!pks[1].pop_verify(&proof));
+    // a PoP is not a signature on the serialized public key under the signing ciphersuite
+    assert!(!pks[0].pop_verify(&sks[0].sign("")));
+
+    let sigs: Vec<Signature<G, Pop>> = sks.iter().map(|sk| sk.sign(msg)).collect();
+    let agg = Signature::aggregate(&sigs[..]);
+    assert!(agg.multisig_verify(&pks[..], msg));
+    assert!(!agg.multisig_verify(&pks[..1], msg));
+}
+
+#[test]
+fn test_typed_pop_g1() {
+    test_typed_pop::<G1>();
+}
+
+#[test]
+fn test_typed_pop_g2() {
+    test_typed_pop::<G2>();
+}
+
+fn test_fast_aggregate<G: BLSSignaturePop<ExpandMsgXmd<Sha256>>>() {
+    let msg = "committee message";
+    let sks: Vec<SecretKey<G, Pop>> = ["key one", "key two", "key three"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, Pop>> = sks.iter().map(SecretKey::public_key).collect();
+    let sigs: Vec<Signature<G, Pop>> = sks.iter().map(|sk| sk.sign(msg)).collect();
+    let agg = Signature::aggregate(&sigs[..]);
+
+    // the precomputed aggregate key agrees with verifying against the key list
+    let apk = PublicKey::aggregate(&pks[..]).unwrap();
+    let points: Vec<_> = pks.iter().map(PublicKey::point).collect();
+    assert_eq!(
+        <G as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::aggregate_public_keys(&points[..]),
+        Some(apk.point())
+    );
+    assert!(agg.fast_aggregate_verify(&pks[..], msg));
+    assert!(agg.fast_aggregate_verify_apk(&apk, msg));
+    assert_eq!(
+        agg.try_fast_aggregate_verify_apk(&apk, "another message"),
+        Err(BlsError::InvalidSignature)
+    );
+    let apk2 = PublicKey::aggregate(&pks[..2]).unwrap();
+    assert!(!agg.fast_aggregate_verify_apk(&apk2, msg));
+    assert!(Signature::aggregate(&sigs[..2]).fast_aggregate_verify_apk(&apk2, msg));
+
+    assert_eq!(
+        PublicKey::<G, Pop>::aggregate(&[]),
+        Err(BlsError::EmptyInput)
+    );
+    assert_eq!(
+        agg.try_fast_aggregate_verify(&[], msg),
+        Err(BlsError::EmptyInput)
+    );
+    let identity = PublicKey::new(CurveProjective::zero());
+    assert_eq!(
+        PublicKey::aggregate(&[pks[0], identity]),
+        Err(BlsError::IdentityKey)
+    );
+    assert_eq!(
+        <G as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::aggregate_public_keys(&[
+            pks[0].point(),
+            identity.point()
+        ]),
+        None
+    );
+}
+
+#[test]
+fn test_fast_aggregate_g1() {
+    test_fast_aggregate::<G1>();
+}
+
+#[test]
+fn test_fast_aggregate_g2() {
+    test_fast_aggregate::<G2>();
+}
+
+// find a point on the curve that is outside the prime-order subgroup
+fn non_subgroup_point<T: CurveProjective>() -> T
+where
+    T::Affine: SubgroupCheck,
+{
+    let mut enc = <T::Affine as CurveAffine>::Compressed::empty();
+    for x in 1u8.. {
+        enc.as_mut()[0] = 0x80;
+        *enc.as_mut().last_mut().unwrap() = x;
+        if let Ok(p) = enc.into_affine_unchecked() {
+            if !p.in_subgroup() {
+                return p.into_projective();
+            }
+        }
+    }
+    unreachable!()
+}
+
+fn test_key_validate<T>()
+where
+    T: BLSSignatureBasic<ExpandMsgXmd<Sha256>>
+        + BLSSignatureAug<ExpandMsgXmd<Sha256>>
+        + BLSSignaturePop<ExpandMsgXmd<Sha256>>,
+    <T::PKType as CurveProjective>::Affine: SubgroupCheck,
+{
+    let msg = "this is the message";
+    let (x_prime, pk) = <T as BLSSigCore<ExpandMsgXmd<Sha256>>>::keygen("this is the key");
+    assert!(<T as BLSSigCore<ExpandMsgXmd<Sha256>>>::key_validate(&pk));
+    assert!(!<T as BLSSigCore<ExpandMsgXmd<Sha256>>>::key_validate(
+        &T::PKType::zero()
+    ));
+    assert!(!<T as BLSSigCore<ExpandMsgXmd<Sha256>>>::key_validate(
+        &non_subgroup_point::<T::PKType>()
+    ));
+
+    // the identity key and the identity signature verify under CoreVerify...
+    let zpk = T::PKType::zero();
+    let zsig = T::zero();
+    assert!(<T as BLSSigCore<ExpandMsgXmd<Sha256>>>::core_verify(
+        zpk,
+        zsig,
+        msg,
+        <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::CSUITE
+    ));
+    assert!(<T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::verify_prevalidated(zpk, zsig, msg));
+    assert!(<T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::pop_verify_prevalidated(zpk, zsig));
+    // ...but not when KeyValidate is applied
+    assert!(!<T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::verify(
+        zpk, zsig, msg
+    ));
+    assert!(!<T as BLSSignatureAug<ExpandMsgXmd<Sha256>>>::verify(
+        zpk, zsig, msg
+    ));
+    assert!(!<T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::verify(
+        zpk, zsig, msg
+    ));
+    assert!(!<T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::pop_verify(
+        zpk, zsig
+    ));
+
+    // an identity key in an aggregate
+    let sig = <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::sign(x_prime, msg);
+    let pks = [pk, zpk];
+    let msgs = [msg, "another message"];
+    assert!(
+        <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::aggregate_verify_prevalidated(
+            &pks, &msgs, sig
+        )
+    );
+    assert!(!<T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::aggregate_verify(&pks, &msgs, sig));
+    let sig = <T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::sign(x_prime, msg);
+    assert!(
+        <T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::multisig_verify_prevalidated(&pks, sig, msg)
+    );
+    assert!(!<T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::multisig_verify(&pks, sig, msg));
+    let sig = <T as BLSSignatureAug<ExpandMsgXmd<Sha256>>>::sign(x_prime, msg);
+    assert!(
+        <T as BLSSignatureAug<ExpandMsgXmd<Sha256>>>::aggregate_verify_prevalidated(
+            &pks, &msgs, sig
+        )
+    );
+    assert!(!<T as BLSSignatureAug<ExpandMsgXmd<Sha256>>>::aggregate_verify(&pks, &msgs, sig));
+}
+
+#[test]
+fn test_key_validate_g1() {
+    test_key_validate::<G1>();
+}
+
+#[test]
+fn test_key_validate_g2() {
+    test_key_validate::<G2>();
+}
+
+fn test_sig_decode<T>()
+where
+    T: BLSSignatureBasic<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+    T::Affine: SubgroupCheck,
+{
+    let msg = "this is the message";
+    let sk = SecretKey::<T, Basic>::keygen("this is the key");
+    let sig = sk.sign(msg);
+    let enc = <T::Affine as CurveAffine>::Compressed::from_affine(sig.point().into_affine());
+    let len = enc.as_ref().len();
+    assert_eq!(
+        Signature::<T, Basic>::from_bytes(enc.as_ref()).unwrap(),
+        sig
+    );
+    assert_eq!(
+        Signature::<T, Basic>::from_bytes(&enc.as_ref()[..len - 1]),
+        Err(BlsError::InvalidEncoding)
+    );
+    assert_eq!(sig.to_bytes().as_ref(), enc.as_ref());
+
+    // the uncompressed encoding, for signatures and public keys
+    let uncompressed = sig.to_uncompressed_bytes();
+    assert_eq!(uncompressed.as_ref().len(), 2 * len);
+    assert_eq!(
+        Signature::<T, Basic>::from_uncompressed_bytes(uncompressed.as_ref()),
+        Ok(sig)
+    );
+    assert_eq!(
+        Signature::<T, Basic>::from_uncompressed_bytes(enc.as_ref()),
+        Err(BlsError::InvalidEncoding)
+    );
+    let pk = sk.public_key();
+    assert_eq!(
+        PublicKey::<T, Basic>::from_uncompressed_bytes(pk.to_uncompressed_bytes().as_ref()),
+        Ok(pk)
+    );
+    assert_eq!(
+        PublicKey::<T, Basic>::from_bytes(pk.to_bytes().as_ref()),
+        Ok(pk)
+    );
+
+    // r * P is a nonzero torsion point outside the prime-order subgroup
+    let torsion = {
+        let r = FrRepr([
+            0xffffffff00000001u64,
+            0x53bda402fffe5bfeu64,
+            0x3339d80809a1d805u64,
+            0x73eda753299d7d48u64,
+        ]);
+        let mut tmp = non_subgroup_point::<T>();
+        tmp.mul_assign(r);
+        assert!(!tmp.is_zero());
+        tmp
+    };
+    let mut bad_sig = sig.point();
+    bad_sig.add_assign(&torsion);
+    assert_eq!(
+        T::try_core_verify(
+            sk.public_key().point(),
+            bad_sig,
+            msg,
+            <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::CSUITE
+        ),
+        Err(BlsError::NotInSubgroup)
+    );
+    let bad_enc = <T::Affine as CurveAffine>::Compressed::from_affine(bad_sig.into_affine());
+    assert_eq!(
+        Signature::<T, Basic>::from_bytes(bad_enc.as_ref()),
+        Err(BlsError::NotInSubgroup)
+    );
+    assert_eq!(
+        Signature::<T, Basic>::from_uncompressed_bytes(bad_sig.to_uncompressed().as_ref()),
+        Err(BlsError::NotInSubgroup)
+    );
+
+    // the point at infinity must not have the sign bit set
+    let mut inf = vec![0u8; len];
+    inf[0] = 0xc0;
+    assert!(Signature::<T, Basic>::from_bytes(&inf)
+        .unwrap()
+        .point()
+        .is_zero());
+    inf[0] = 0xe0;
+    assert_eq!(
+        Signature::<T, Basic>::from_bytes(&inf),
+        Err(BlsError::InvalidEncoding)
+    );
+    let mut inf = vec![0u8; 2 * len];
+    inf[0] = 0x40;
+    assert!(Signature::<T, Basic>::from_uncompressed_bytes(&inf)
+        .unwrap()
+        .point()
+        .is_zero());
+    inf[0] = 0x60;
+    assert_eq!(
+        Signature::<T, Basic>::from_uncompressed_bytes(&inf),
+        Err(BlsError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn test_sig_decode_g1() {
+    test_sig_decode::<G1>();
+}
+
+#[test]
+fn test_sig_decode_g2() {
+    test_sig_decode::<G2>();
+}
+
+fn test_errors<T>()
+where
+    T: BLSSignatureBasic<ExpandMsgXmd<Sha256>>
+        + BLSSignatureAug<ExpandMsgXmd<Sha256>>
+        + BLSSignaturePop<ExpandMsgXmd<Sha256>>,
+    <T::PKType as CurveProjective>::Affine: SubgroupCheck,
+{
+    let msg = "this is the message";
+    let (x_prime, pk) = <T as BLSSigCore<ExpandMsgXmd<Sha256>>>::keygen("this is the key");
+    assert_eq!(
+        <T as BLSSigCore<ExpandMsgXmd<Sha256>>>::try_key_validate(&T::PKType::zero()),
+        Err(BlsError::IdentityKey)
+    );
+    assert_eq!(
+        <T as BLSSigCore<ExpandMsgXmd<Sha256>>>::try_key_validate(
+            &non_subgroup_point::<T::PKType>()
+        ),
+        Err(BlsError::NotInSubgroup)
+    );
+
+    let sig = <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::sign(x_prime, msg);
+    assert_eq!(
+        <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::try_verify(pk, sig, msg),
+        Ok(())
+    );
+    assert_eq!(
+        <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::try_verify(pk, sig, "another message"),
+        Err(BlsError::InvalidSignature)
+    );
+    assert_eq!(
+        <T as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::try_aggregate_verify(
+            &[pk, pk],
+            &[msg, msg],
+            sig
+        ),
+        Err(BlsError::DuplicateMessage)
+    );
+    assert_eq!(
+        <T as BLSSignatureAug<ExpandMsgXmd<Sha256>>>::try_verify(T::PKType::zero(), sig, msg),
+        Err(BlsError::IdentityKey)
+    );
+
+    let sig = <T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::pop_prove("this is the key");
+    assert_eq!(
+        <T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::try_pop_verify(pk, sig),
+        Ok(())
+    );
+    assert_eq!(
+        <T as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::try_multisig_verify(&[pk], sig, msg),
+        Err(BlsError::InvalidSignature)
+    );
+}
+
+#[test]
+fn test_errors_g1() {
+    test_errors::<G1>();
+}
+
+#[test]
+fn test_errors_g2() {
+    test_errors::<G2>();
+}
+
+fn test_agg_inputs<T>()
+where
+    T: BLSSignatureBasic<ExpandMsgXmd<Sha256>>
+        + BLSSignatureAug<ExpandMsgXmd<Sha256>>
+        + BLSSignaturePop<ExpandMsgXmd<Sha256>>,
+{
+    type Xmd = ExpandMsgXmd<Sha256>;
+    let msgs = ["message one", "message two"];
+    let (x1, pk1) = <T as BLSSigCore<Xmd>>::keygen("key one");
+    let (_, pk2) = <T as BLSSigCore<Xmd>>::keygen("key two");
+    let pks = [pk1, pk2];
+    let no_pks: [T::PKType; 0] = [];
+    let no_msgs: [&str; 0] = [];
+    let dst = &[1u8][..];
+
+    // core: a signature on msgs[0] alone must not verify against two keys and one message
+    let sig = <T as BLSSigCore<Xmd>>::core_sign(x1, msgs[0], dst);
+    assert!(<T as BLSSigCore<Xmd>>::core_verify(pk1, sig, msgs[0], dst));
+    assert_eq!(
+        <T as BLSSigCore<Xmd>>::try_core_aggregate_verify(&pks, &msgs[..1], sig, dst),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSigCore<Xmd>>::try_core_aggregate_verify(&pks[..1], &msgs, sig, dst),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSigCore<Xmd>>::try_core_aggregate_verify(&no_pks, &no_msgs, T::zero(), dst),
+        Err(BlsError::EmptyInput)
+    );
+
+    // Basic
+    let sig = <T as BLSSignatureBasic<Xmd>>::sign(x1, msgs[0]);
+    assert_eq!(
+        <T as BLSSignatureBasic<Xmd>>::try_aggregate_verify(&pks, &msgs[..1], sig),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSignatureBasic<Xmd>>::try_aggregate_verify_prevalidated(&pks, &msgs[..1], sig),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSignatureBasic<Xmd>>::try_aggregate_verify(&no_pks, &no_msgs, T::zero()),
+        Err(BlsError::EmptyInput)
+    );
+
+    // Aug
+    let sig = <T as BLSSignatureAug<Xmd>>::sign(x1, msgs[0]);
+    assert_eq!(
+        <T as BLSSignatureAug<Xmd>>::try_aggregate_verify(&pks, &msgs[..1], sig),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSignatureAug<Xmd>>::try_aggregate_verify_prevalidated(&pks, &msgs[..1], sig),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSignatureAug<Xmd>>::try_aggregate_verify(&no_pks, &no_msgs, T::zero()),
+        Err(BlsError::EmptyInput)
+    );
+
+    // Pop
+    let sig = <T as BLSSignaturePop<Xmd>>::sign(x1, msgs[0]);
+    assert_eq!(
+        <T as BLSSignaturePop<Xmd>>::try_aggregate_verify(&pks, &msgs[..1], sig),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSignaturePop<Xmd>>::try_aggregate_verify_prevalidated(&pks, &msgs[..1], sig),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        <T as BLSSignaturePop<Xmd>>::try_aggregate_verify(&no_pks, &no_msgs, T::zero()),
+        Err(BlsError::EmptyInput)
+    );
+    assert_eq!(
+        <T as BLSSignaturePop<Xmd>>::try_multisig_verify(&no_pks, T::zero(), msgs[0]),
+        Err(BlsError::EmptyInput)
+    );
+}
+
+#[test]
+fn test_agg_inputs_g1() {
+    test_agg_inputs::<G1>();
+}
+
+#[test]
+fn test_agg_inputs_g2() {
+    test_agg_inputs::<G2>();
+}
+
+fn test_batch<G, S>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+{
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let msgs = ["message one", "message two", "message three", "message one"];
+    let sks: Vec<SecretKey<G, S>> = ["key one", "key two", "key three", "key four"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, S>> = sks.iter().map(SecretKey::public_key).collect();
+    let mut sigs: Vec<Signature<G, S>> = sks.iter().zip(&msgs).map(|(sk, m)| sk.sign(m)).collect();
+    assert_eq!(
+        Signature::try_batch_verify(&pks[..], &msgs[..], &sigs[..], &mut rng),
+        Ok(())
+    );
+
+    // swapping two signatures leaves their sum unchanged, but must not pass batch verification
+    sigs.swap(0, 1);
+    assert_eq!(
+        Signature::try_batch_verify(&pks[..], &msgs[..], &sigs[..], &mut rng),
+        Err(BlsError::InvalidSignature)
+    );
+    sigs.swap(0, 1);
+
+    sigs[2] = sks[2].sign("not message three");
+    assert!(!Signature::batch_verify(
+        &pks[..],
+        &msgs[..],
+        &sigs[..],
+        &mut rng
+    ));
+
+    assert_eq!(
+        Signature::try_batch_verify(&pks[..], &msgs[..], &sigs[..3], &mut rng),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        Signature::try_batch_verify(&pks[..3], &msgs[..], &sigs[..], &mut rng),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        Signature::try_batch_verify(&pks[..0], &msgs[..0], &sigs[..0], &mut rng),
+        Err(BlsError::EmptyInput)
+    );
+}
+
+#[test]
+fn test_batch_g1() {
+    test_batch::<G1, Basic>();
+    test_batch::<G1, Aug>();
+    test_batch::<G1, Pop>();
+}
+
+#[test]
+fn test_batch_g2() {
+    test_batch::<G2, Basic>();
+    test_batch::<G2, Aug>();
+    test_batch::<G2, Pop>();
+}
+
+#[test]
+fn test_bitfield() {
+    let mut bits = Bitfield::new();
+    assert!(bits.is_empty());
+    bits.set(3);
+    bits.set(17);
+    bits.set(3);
+    assert!(bits.get(3) && bits.get(17) && !bits.get(4) && !bits.get(1000));
+    assert_eq!(bits.count(), 2);
+    assert_eq!(bits.iter().collect::<Vec<_>>(), vec![3, 17]);
+
+    let mut other = Bitfield::new();
+    other.set(4);
+    assert!(!bits.intersects(&other));
+    other.set(17);
+    assert!(bits.intersects(&other));
+    bits.union_with(&other);
+    assert_eq!(bits.iter().collect::<Vec<_>>(), vec![3, 4, 17]);
+
+    // clearing the high bits gives a bitfield equal to one that never had them
+    bits.clear(17);
+    bits.clear(4);
+    let mut expect = Bitfield::new();
+    expect.set(3);
+    assert_eq!(bits, expect);
+    bits.clear(3);
+    assert!(bits.is_empty());
+    assert_eq!(bits, Bitfield::new());
+}
+
+fn test_aggregator<G: BLSSignaturePop<ExpandMsgXmd<Sha256>>>() {
+    let msg = "gossiped message";
+    let sks: Vec<SecretKey<G, Pop>> = ["key one", "key two", "key three", "key four"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, Pop>> = sks.iter().map(SecretKey::public_key).collect();
+    let sigs: Vec<Signature<G, Pop>> = sks.iter().map(|sk| sk.sign(msg)).collect();
+
+    let mut agg = Aggregator::new(4);
+    assert_eq!(agg.try_verify(msg), Err(BlsError::EmptyInput));
+    agg.add(0, &pks[0], &sigs[0]).unwrap();
+    agg.add(2, &pks[2], &sigs[2]).unwrap();
+    assert_eq!(
+        agg.add(2, &pks[2], &sigs[2]),
+        Err(BlsError::DuplicateSigner)
+    );
+    // indices past the committee are rejected rather than growing the bitfield
+    assert_eq!(
+        agg.add(4, &pks[3], &sigs[3]),
+        Err(BlsError::SignerOutOfRange)
+    );
+    assert_eq!(
+        agg.add(usize::MAX, &pks[3], &sigs[3]),
+        Err(BlsError::SignerOutOfRange)
+    );
+    assert_eq!(agg.count(), 2);
+    assert!(agg.contains(2) && !agg.contains(1));
+    assert!(agg.verify(msg));
+    assert_eq!(agg.signature(), Signature::aggregate(&[sigs[0], sigs[2]]));
+    assert_eq!(
+        agg.public_key(),
+        PublicKey::aggregate(&[pks[0], pks[2]]).unwrap()
+    );
+
+    let mut other = Aggregator::new(4);
+    other.add(1, &pks[1], &sigs[1]).unwrap();
+    other.add(3, &pks[3], &sigs[3]).unwrap();
+    let before = agg.clone();
+    // other's signers must fit in agg's committee
+    let mut small = Aggregator::new(3);
+    assert_eq!(small.merge(&other), Err(BlsError::SignerOutOfRange));
+    assert_eq!(small.count(), 0);
+    agg.merge(&other).unwrap();
+    assert_eq!(agg.count(), 4);
+    assert!(agg.signature().fast_aggregate_verify(&pks[..], msg));
+    assert!(agg.verify(msg));
+    // overlapping signers are rejected, and the aggregate is unchanged
+    assert_eq!(agg.merge(&other), Err(BlsError::DuplicateSigner));
+    assert_eq!(agg.count(), 4);
+
+    agg.remove(1, &pks[1], &sigs[1]).unwrap();
+    agg.remove(3, &pks[3], &sigs[3]).unwrap();
+    assert_eq!(
+        agg.remove(3, &pks[3], &sigs[3]),
+        Err(BlsError::MissingSigner)
+    );
+    assert_eq!(agg.signature(), before.signature());
+    assert_eq!(agg.public_key(), before.public_key());
+    assert_eq!(agg.participants(), before.participants());
+
+    // a contribution on the wrong message breaks verification
+    agg.add(1, &pks[1], &sks[1].sign("another message"))
+        .unwrap();
+    assert_eq!(agg.try_verify(msg), Err(BlsError::InvalidSignature));
+}
+
+#[test]
+fn test_aggregator_g1() {
+    test_aggregator::<G1>();
+}
+
+#[test]
+fn test_aggregator_g2() {
+    test_aggregator::<G2>();
+}
+
+#[test]
+fn test_bitfield_bytes() {
+    let mut bits = Bitfield::new();
+    bits.set(0);
+    bits.set(9);
+    assert_eq!(bits.bit_len(), 10);
+    assert_eq!(bits.to_bytes(10), Ok(vec![0x01, 0x02]));
+    assert_eq!(bits.to_bytes(17), Ok(vec![0x01, 0x02, 0x00]));
+    assert_eq!(bits.to_bytes(9), Err(BlsError::SignerOutOfRange));
+    assert_eq!(Bitfield::from_bytes(&[0x01, 0x02], 10), Ok(bits.clone()));
+    assert_eq!(Bitfield::from_bytes(&[0x01, 0x02, 0x00], 17), Ok(bits));
+    assert_eq!(Bitfield::new().to_bytes(0), Ok(vec![]));
+
+    // wrong length, and bits past the end
+    assert_eq!(
+        Bitfield::from_bytes(&[0x01, 0x02, 0x00], 10),
+        Err(BlsError::InvalidEncoding)
+    );
+    assert_eq!(
+        Bitfield::from_bytes(&[0x01, 0x04], 10),
+        Err(BlsError::InvalidEncoding)
+    );
+}
+
+fn test_committee<G: BLSSignaturePop<ExpandMsgXmd<Sha256>>>() {
+    let msg = "committee message";
+    let sks: Vec<SecretKey<G, Pop>> = (0..5u8).map(|i| SecretKey::keygen([i])).collect();
+    let pks: Vec<PublicKey<G, Pop>> = sks.iter().map(SecretKey::public_key).collect();
+    let committee = Committee::new(pks.clone()).unwrap();
+    assert_eq!(committee.len(), 5);
+    assert_eq!(committee.public_keys(), &pks[..]);
+
+    // minority and majority participation take different paths to the aggregate key
+    for signers in [vec![1], vec![0, 3], vec![0, 2, 3, 4], vec![0, 1, 2, 3, 4]].iter() {
+        let mut bits = Bitfield::new();
+        for &i in signers {
+            bits.set(i);
+        }
+        let sigs: Vec<Signature<G, Pop>> = signers.iter().map(|&i| sks[i].sign(msg)).collect();
+        let agg = Signature::aggregate(&sigs[..]);
+        let signer_pks: Vec<PublicKey<G, Pop>> = signers.iter().map(|&i| pks[i]).collect();
+        assert_eq!(
+            committee.aggregate_public_key(&bits),
+            PublicKey::aggregate(&signer_pks[..])
+        );
+        assert!(committee.verify_bitfield_aggregate(&bits, msg, &agg));
+        assert!(!committee.verify_bitfield_aggregate(&bits, "another message", &agg));
+
+        let enc = committee.bitfield_to_bytes(&bits).unwrap();
+        assert_eq!(enc.len(), 1);
+        assert_eq!(committee.bitfield_from_bytes(&enc[..]), Ok(bits.clone()));
+
+        // claiming an extra signer fails
+        let extra = (0..5).find(|i| !signers.contains(i));
+        if let Some(i) = extra {
+            bits.set(i);
+            assert_eq!(
+                committee.try_verify_bitfield_aggregate(&bits, msg, &agg),
+                Err(BlsError::InvalidSignature)
+            );
+        }
+    }
+
+    let mut bits = Bitfield::new();
+    let sig = sks[0].sign(msg);
+    assert_eq!(
+        committee.try_verify_bitfield_aggregate(&bits, msg, &sig),
+        Err(BlsError::EmptyInput)
+    );
+    bits.set(5);
+    assert_eq!(
+        committee.try_verify_bitfield_aggregate(&bits, msg, &sig),
+        Err(BlsError::SignerOutOfRange)
+    );
+    assert_eq!(
+        committee.bitfield_to_bytes(&bits),
+        Err(BlsError::SignerOutOfRange)
+    );
+
+    assert_eq!(
+        Committee::<G>::new(vec![]).map(|c| c.len()),
+        Err(BlsError::EmptyInput)
+    );
+    let identity = PublicKey::new(CurveProjective::zero());
+    assert_eq!(
+        Committee::new(vec![pks[0], identity]).map(|c| c.len()),
+        Err(BlsError::IdentityKey)
+    );
+}
+
+#[test]
+fn test_committee_g1() {
+    test_committee::<G1>();
+}
+
+#[test]
+fn test_committee_g2() {
+    test_committee::<G2>();
+}
+
+#[test]
+fn test_ciphersuite_ids() {
+    type Xmd512 = ExpandMsgXmd<Sha512>;
+    type Shake = ExpandMsgXof<Shake128>;
+    assert_eq!(
+        <G1 as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::CSUITE,
+        &b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_"[..]
+    );
+    assert_eq!(
+        <G2 as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::CSUITE_POP,
+        &b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_"[..]
+    );
+    assert_eq!(
+        <G1 as BLSSignatureAug<Xmd512>>::CSUITE,
+        &b"BLS_SIG_BLS12381G1_XMD:SHA-512_SSWU_RO_AUG_"[..]
+    );
+    assert_eq!(
+        <G2 as BLSSignaturePop<Shake>>::CSUITE,
+        &b"BLS_SIG_BLS12381G2_XOF:SHAKE128_SSWU_RO_POP_"[..]
+    );
+    assert_eq!(
+        <G1 as BLSSignaturePop<ExpandMsgXof<Shake256>>>::CSUITE_POP,
+        &b"BLS_POP_BLS12381G1_XOF:SHAKE256_SSWU_RO_POP_"[..]
+    );
+}
+
+fn test_expander<G, S, X>()
+where
+    G: BLSSigCore<X> + BLSSigCore<ExpandMsgXmd<Sha256>>,
+    S: Scheme<G, X> + Scheme<G, ExpandMsgXmd<Sha256>>,
+    X: ExpandMsg,
+{
+    let msg = "message";
+    let sk = SecretKey::<G, S, X>::keygen("key");
+    let pk = sk.public_key();
+    let sig = sk.sign(msg);
+    assert!(sig.verify(&pk, msg));
+    assert!(!sig.verify(&pk, "another message"));
+
+    // the same key under the default ciphersuite gives a different signature
+    let sk_256 = SecretKey::<G, S>::keygen("key");
+    let sig_256 = sk_256.sign(msg);
+    assert!(sig.point() != sig_256.point());
+    assert!(!Signature::<G, S>::new(sig.point()).verify(&sk_256.public_key(), msg));
+}
+
+#[test]
+fn test_expanders_g1() {
+    test_expander::<G1, Basic, ExpandMsgXmd<Sha512>>();
+    test_expander::<G1, Aug, ExpandMsgXof<Shake128>>();
+    test_expander::<G1, Pop, ExpandMsgXof<Shake256>>();
+}
+
+#[test]
+fn test_expanders_g2() {
+    test_expander::<G2, Pop, ExpandMsgXmd<Sha512>>();
+    test_expander::<G2, Basic, ExpandMsgXof<Shake128>>();
+    test_expander::<G2, Aug, ExpandMsgXof<Shake256>>();
+}
+
+#[test]
+fn test_registry() {
+    // every ciphersuite is registered exactly once
+    let ids: HashSet<&[u8]> = ciphersuites().iter().map(|cs| cs.id()).collect();
+    assert_eq!(ids.len(), 24);
+    assert_eq!(ids.len(), ciphersuites().len());
+    for &cs in ciphersuites() {
+        assert_eq!(ciphersuite(cs.id()).unwrap().id(), cs.id());
+    }
+    assert_eq!(
+        ciphersuite("BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_FOO_").map(|cs| cs.id()),
+        Err(BlsError::UnknownCiphersuite)
+    );
+    // PoP tags are not signing ciphersuites
+    assert!(ciphersuite("BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_").is_err());
+
+    // the registry agrees with the typed API
+    let cs = ciphersuite("BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_").unwrap();
+    let sk = SecretKey::<G2, Pop>::keygen("key");
+    let sk_bytes = sk.to_bytes();
+    assert_eq!(sk_bytes.len(), 32);
+    assert_eq!(
+        SecretKey::<G2, Pop>::from_bytes(&sk_bytes[..])
+            .unwrap()
+            .x_prime(),
+        sk.x_prime()
+    );
+    assert_eq!(
+        cs.public_key(&sk_bytes[..]),
+        Ok(sk.public_key().to_bytes().to_vec())
+    );
+    assert_eq!(
+        cs.sign(&sk_bytes[..], b"msg"),
+        Ok(sk.sign("msg").to_bytes().to_vec())
+    );
+}
+
+#[test]
+fn test_registry_sign_verify() {
+    let msgs: [&[u8]; 2] = [b"message one", b"message two"];
+    for &cs in ciphersuites() {
+        let sks: Vec<Vec<u8>> = ["key one", "key two"]
+            .iter()
+            .map(|ikm| SecretKey::<G1, Basic>::keygen(ikm).to_bytes().to_vec())
+            .collect();
+        let pks: Vec<Vec<u8>> = sks.iter().map(|sk| cs.public_key(sk).unwrap()).collect();
+        let sigs: Vec<Vec<u8>> = sks
+            .iter()
+            .zip(&msgs)
+            .map(|(sk, msg)| cs.sign(sk, msg).unwrap())
+            .collect();
+        assert_eq!(cs.verify(&pks[0], msgs[0], &sigs[0]), Ok(()));
+        assert_eq!(
+            cs.verify(&pks[0], msgs[1], &sigs[0]),
+            Err(BlsError::InvalidSignature)
+        );
+        // a signature from another group does not decode
+        assert_eq!(
+            cs.verify(&pks[0], msgs[0], &pks[0]),
+            Err(BlsError::InvalidEncoding)
+        );
+
+        let sig_refs: Vec<&[u8]> = sigs.iter().map(|s| &s[..]).collect();
+        let pk_refs: Vec<&[u8]> = pks.iter().map(|pk| &pk[..]).collect();
+        let agg = cs.aggregate(&sig_refs[..]).unwrap();
+        assert_eq!(cs.aggregate_verify(&pk_refs[..], &msgs[..], &agg), Ok(()));
+        assert_eq!(
+            cs.aggregate_verify(&pk_refs[..1], &msgs[..], &agg),
+            Err(BlsError::LengthMismatch)
+        );
+        assert_eq!(cs.aggregate(&[]), Err(BlsError::EmptyInput));
+    }
+    assert_eq!(
+        SecretKey::<G1, Basic>::from_bytes(&[0xff; 32]).map(|sk| sk.to_bytes()),
+        Err(BlsError::InvalidEncoding)
+    );
+    assert!(SecretKey::<G1, Basic>::from_bytes(&[0; 31]).is_err());
+    // zero is less than the group order, but its public key would be the identity
+    assert_eq!(
+        SecretKey::<G1, Basic>::from_bytes(&[0; 32]).map(|sk| sk.to_bytes()),
+        Err(BlsError::InvalidEncoding)
+    );
+}
+
+fn test_custom_dst<G>()
+where
+    G: BLSSignatureBasic<ExpandMsgXmd<Sha256>>
+        + BLSSignatureAug<ExpandMsgXmd<Sha256>>
+        + BLSSignaturePop<ExpandMsgXmd<Sha256>>,
+{
+    let msgs = ["message one", "message two"];
+
+    // using the ciphersuite ID as the DST reproduces the standard scheme
+    let basic = SchemeBuilder::<G, Basic>::new()
+        .dst(<G as BLSSignatureBasic<ExpandMsgXmd<Sha256>>>::CSUITE)
+        .build()
+        .unwrap();
+    let sk = SecretKey::<G, Basic>::keygen("key one");
+    assert_eq!(basic.sign(&sk, msgs[0]), sk.sign(msgs[0]));
+    let aug = SchemeBuilder::<G, Aug>::new()
+        .dst(<G as BLSSignatureAug<ExpandMsgXmd<Sha256>>>::CSUITE)
+        .build()
+        .unwrap();
+    let sk = SecretKey::<G, Aug>::keygen("key one");
+    assert_eq!(aug.sign(&sk, msgs[0]), sk.sign(msgs[0]));
+
+    // Basic: signatures don't verify across protocols, and aggregates need distinct messages
+    let basic = SchemeBuilder::<G, Basic>::new()
+        .dst("PROTOCOL-A")
+        .build()
+        .unwrap();
+    let sks: Vec<SecretKey<G, Basic>> = ["key one", "key two"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, Basic>> = sks.iter().map(SecretKey::public_key).collect();
+    let sig = basic.sign(&sks[0], msgs[0]);
+    assert!(basic.verify(&pks[0], msgs[0], &sig));
+    assert!(!sig.verify(&pks[0], msgs[0]));
+    assert!(!basic.verify(&pks[0], msgs[0], &sks[0].sign(msgs[0])));
+    let other = SchemeBuilder::<G, Basic>::new()
+        .dst("PROTOCOL-B")
+        .build()
+        .unwrap();
+    assert_eq!(
+        other.try_verify(&pks[0], msgs[0], &sig),
+        Err(BlsError::InvalidSignature)
+    );
+    let sigs: Vec<Signature<G, Basic>> = sks.iter().map(|sk| basic.sign(sk, msgs[0])).collect();
+    assert_eq!(
+        basic.try_aggregate_verify(
+            &pks[..],
+            &[msgs[0], msgs[0]],
+            &Signature::aggregate(&sigs[..])
+        ),
+        Err(BlsError::DuplicateMessage)
+    );
+    let sigs: Vec<Signature<G, Basic>> = sks
+        .iter()
+        .zip(&msgs)
+        .map(|(sk, msg)| basic.sign(sk, msg))
+        .collect();
+    assert!(basic.aggregate_verify(&pks[..], &msgs[..], &Signature::aggregate(&sigs[..])));
+
+    // Aug: the same message under two keys aggregates
+    let aug = SchemeBuilder::<G, Aug>::new()
+        .dst("PROTOCOL-A")
+        .build()
+        .unwrap();
+    let sks: Vec<SecretKey<G, Aug>> = ["key one", "key two"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, Aug>> = sks.iter().map(SecretKey::public_key).collect();
+    let sigs: Vec<Signature<G, Aug>> = sks.iter().map(|sk| aug.sign(sk, msgs[0])).collect();
+    let agg = Signature::aggregate(&sigs[..]);
+    assert!(aug.aggregate_verify(&pks[..], &[msgs[0], msgs[0]], &agg));
+    assert!(!agg.aggregate_verify(&pks[..], &[msgs[0], msgs[0]]));
+
+    // Pop: PoPs are separated from signatures and from the standard PoP tag
+    let pop = SchemeBuilder::<G, Pop>::new()
+        .dst("PROTOCOL-A")
+        .pop_dst("PROTOCOL-A-POP")
+        .build()
+        .unwrap();
+    let sks: Vec<SecretKey<G, Pop>> = ["key one", "key two"]
+        .iter()
+        .map(SecretKey::keygen)
+        .collect();
+    let pks: Vec<PublicKey<G, Pop>> = sks.iter().map(SecretKey::public_key).collect();
+    let proof = pop.pop_prove(&sks[0]);
+    assert!(pop.pop_verify(&pks[0], &proof));
+    assert!(!pks[0].pop_verify(&proof));
+    assert!(!pop.pop_verify(&pks[0], &sks[0].pop_prove()));
+    assert!(!pop.pop_verify(&pks[1], &proof));
+    let sigs: Vec<Signature<G, Pop>> = sks.iter().map(|sk| pop.sign(sk, msgs[1])).collect();
+    let agg = Signature::aggregate(&sigs[..]);
+    assert!(pop.fast_aggregate_verify(&pks[..], msgs[1], &agg));
+    assert!(!pop.fast_aggregate_verify(&pks[..1], msgs[1], &agg));
+    assert!(!agg.fast_aggregate_verify(&pks[..], msgs[1]));
+}
+
+#[test]
+fn test_custom_dst_g1() {
+    test_custom_dst::<G1>();
+}
+
+#[test]
+fn test_custom_dst_g2() {
+    test_custom_dst::<G2>();
+}
+
+#[test]
+fn test_custom_dst_validation() {
+    assert_eq!(
+        SchemeBuilder::<G1, Basic>::new()
+            .build()
+            .map(|s| s.dst().len()),
+        Err(BlsError::InvalidDst)
+    );
+    assert_eq!(
+        SchemeBuilder::<G1, Aug>::new()
+            .dst("")
+            .build()
+            .map(|s| s.dst().len()),
+        Err(BlsError::InvalidDst)
+    );
+    assert_eq!(
+        SchemeBuilder::<G2, Pop>::new()
+            .dst("A")
+            .build()
+            .map(|s| s.dst().len()),
+        Err(BlsError::InvalidDst)
+    );
+    assert_eq!(
+        SchemeBuilder::<G2, Pop>::new()
+            .dst("A")
+            .pop_dst("A")
+            .build()
+            .map(|s| s.dst().len()),
+        Err(BlsError::InvalidDst)
+    );
+
+    // DSTs up to 255 bytes are used as is; longer ones are hashed
+    let dst = [b'x'; 256];
+    let scheme = SchemeBuilder::<G1, Basic>::new()
+        .dst(&dst[..255])
+        .build()
+        .unwrap();
+    assert_eq!(scheme.dst(), &dst[..255]);
+    let scheme = SchemeBuilder::<G1, Basic>::new()
+        .dst(&dst[..])
+        .build()
+        .unwrap();
+    let mut expect = b"H2C-OVERSIZE-DST-".to_vec();
+    expect.extend_from_slice(&dst[..]);
+    assert_eq!(scheme.dst(), &Sha256::digest(&expect[..])[..]);
+    assert_eq!(ExpandMsgXmd::<Sha512>::oversize_dst(&dst[..]).len(), 64);
+    assert_eq!(ExpandMsgXof::<Shake128>::oversize_dst(&dst[..]).len(), 32);
+    let scheme = SchemeBuilder::<G2, Pop, ExpandMsgXof<Shake256>>::new()
+        .dst(&dst[..])
+        .pop_dst(&[b'y'; 300][..])
+        .build()
+        .unwrap();
+    assert_eq!(scheme.dst().len(), 32);
+    assert_eq!(scheme.pop_dst().len(), 32);
+    let sk = SecretKey::keygen("key");
+    assert!(scheme.verify(&sk.public_key(), "msg", &scheme.sign(&sk, "msg")));
+    assert!(scheme.pop_verify(&sk.public_key(), &scheme.pop_prove(&sk)));
+}
+
+#[test]
+fn test_zeroize() {
+    let mut x_prime = xprime_from_sk("this is the key");
+    assert!(!x_prime.is_zero());
+    _wipe_scalar(&mut x_prime);
+    assert!(x_prime.is_zero());
+
+    // dropping a secret key wipes its scalar
+    let mut sk = ManuallyDrop::new(SecretKey::<G1, Aug>::keygen("this is the key"));
+    assert!(!sk.x_prime().is_zero());
+    unsafe { ptr::drop_in_place(&mut *sk) };
+    assert!(sk.x_prime().is_zero());
+
+    // signing wipes only its own copies, so the key still works afterwards
+    let sk = SecretKey::<G2, Pop>::keygen("this is the key");
+    let (x_prime, pk) = <G2 as BLSSigCore<ExpandMsgXmd<Sha256>>>::keygen("this is the key");
+    let sig = sk.sign("msg");
+    assert_eq!(sig, sk.sign("msg"));
+    assert_eq!(
+        sig.point(),
+        <G2 as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::sign(x_prime, "msg")
+    );
+    assert_eq!(
+        sk.pop_prove().point(),
+        <G2 as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::pop_prove_with(x_prime, &pk)
+    );
+    assert!(!sk.x_prime().is_zero());
+    let sk = SecretKey::<G1, Aug>::from_bytes(&sk.to_bytes()[..]).unwrap();
+    assert!(sk.public_key().verify("msg", &sk.sign("msg")));
+}
+
+fn test_ct_mul<G: CtSelect<Scalar = Fr>>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let mut minus_one = Fr::one();
+    minus_one.negate();
+    let mut scalars = vec![Fr::zero(), Fr::one(), minus_one];
+    scalars.extend((0..32).map(|_| Fr::random(&mut rng)));
+    for x in scalars {
+        let p = G::random(&mut rng);
+        let mut expect = p;
+        expect.mul_assign(x);
+        let mut result = p;
+        ct_mul_assign(&mut result, &x);
+        assert_eq!(result, expect);
+    }
+}
+
+#[test]
+fn test_ct_mul_g1() {
+    test_ct_mul::<G1>();
+}
+
+#[test]
+fn test_ct_mul_g2() {
+    test_ct_mul::<G2>();
+}
+
+#[test]
+fn test_parse_path() {
+    assert_eq!(parse_path("m"), Ok(vec![]));
+    assert_eq!(
+        parse_path("m/12381/3600/0/0/0"),
+        Ok(vec![12381, 3600, 0, 0, 0])
+    );
+    assert_eq!(parse_path("m/4294967295"), Ok(vec![4294967295]));
+    for bad in &[
+        "",
+        "/0",
+        "0/1",
+        "M/0",
+        "m/",
+        "m//0",
+        "m/0/",
+        "m/-1",
+        "m/+1",
+        "m/ 1",
+        "m/1'",
+        "m/0x10",
+        "m/4294967296",
+    ] {
+        assert_eq!(parse_path(bad), Err(BlsError::InvalidPath), "{}", bad);
+    }
+}
+
+#[test]
+fn test_derive_path() {
+    let seed = [7u8; 32];
+    let master = derive_master_sk(&seed[..]).unwrap();
+    let mut expect = master;
+    for &index in &[12381, 3600, 5, 0, 0] {
+        expect = derive_child_sk(&expect, index);
+    }
+    assert_eq!(derive_path(&seed[..], "m/12381/3600/5/0/0"), Ok(expect));
+    assert_eq!(derive_path(&seed[..], "m"), Ok(master));
+    assert_eq!(derive_master_sk(&seed[..31]), None);
+    assert_eq!(derive_path(&seed[..31], "m/0"), Err(BlsError::InvalidSeed));
+    assert_eq!(derive_path(&seed[..], "m/x"), Err(BlsError::InvalidPath));
+
+    let sk = SecretKey::<G1, Pop>::derive_path(&seed[..], "m/12381/3600/5/0/0").unwrap();
+    assert_eq!(sk.x_prime(), expect);
+    let child = SecretKey::<G2, Basic>::derive_master(&seed[..])
+        .unwrap()
+        .derive_child(12381);
+    assert_eq!(child.x_prime(), derive_child_sk(&master, 12381));
+}
+
+#[test]
+fn test_normalize_password() {
+    // the EIP-2335 test password, in Fraktur letters
+    assert_eq!(
+        normalize_password("\u{1d531}\u{1d522}\u{1d530}\u{1d531}\u{1d52d}\u{1d51e}\u{1d530}\u{1d530}\u{1d534}\u{1d52c}\u{1d52f}\u{1d521}\u{1f511}"),
+        hex!("7465737470617373776f7264f09f9491").to_vec()
+    );
+    // control codes are removed, but spaces are not
+    assert_eq!(
+        normalize_password("a\u{0}b\tc\u{7f}d\u{85}e f"),
+        b"abcde f".to_vec()
+    );
+    // compatibility decomposition
+    assert_eq!(
+        normalize_password("\u{fb01}\u{e9}"),
+        "fie\u{301}".as_bytes().to_vec()
+    );
+}
+
+#[test]
+fn test_keystore() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let sk = SecretKey::<G2, Pop>::keygen("this is the key");
+    // cheap parameters, so that the test runs quickly
+    for &kdf in &[Kdf::Scrypt { n: 16, r: 8, p: 1 }, Kdf::Pbkdf2 { c: 16 }] {
+        let ks = Keystore::encrypt(&sk, "password", "m/12381/3600/0/0/0", kdf, &mut rng)
+            .unwrap()
+            .with_description("a test key");
+        assert_eq!(ks.kdf(), kdf);
+        assert_eq!(ks.path(), "m/12381/3600/0/0/0");
+        assert_eq!(ks.description(), "a test key");
+        assert_eq!(ks.pubkey(), &sk.public_key().to_bytes()[..]);
+        assert_eq!(ks.uuid().len(), 36);
+        assert_eq!(&ks.uuid()[14..15], "4");
+
+        let decrypted: SecretKey<G2, Pop> = ks.decrypt("password").unwrap();
+        assert_eq!(decrypted.x_prime(), sk.x_prime());
+        // normalization makes these equivalent
+        assert!(ks
+            .decrypt::<G2, Pop, ExpandMsgXmd<Sha256>>("pass\u{7}word")
+            .is_ok());
+        assert_eq!(
+            ks.decrypt::<G2, Pop, ExpandMsgXmd<Sha256>>("Password")
+                .map(|_| ()),
+            Err(BlsError::InvalidPassword)
+        );
+        // the recorded public key is in G1, so the key cannot be read back as a G1 signing key
+        assert_eq!(
+            ks.decrypt::<G1, Pop, ExpandMsgXmd<Sha256>>("password")
+                .map(|_| ()),
+            Err(BlsError::InvalidKeystore)
+        );
+
+        let json = ks.to_json();
+        assert_eq!(Keystore::from_json(&json), Ok(ks.clone()));
+        for (from, to) in &[
+            ("\"version\": 4", "\"version\": 3"),
+            ("\"aes-128-ctr\"", "\"aes-256-ctr\""),
+            ("\"sha256\"", "\"sha512\""),
+            ("\"hmac-sha256\"", "\"hmac-sha512\""),
+            ("\"dklen\": 32", "\"dklen\": 16"),
+            ("\"iv\": \"", "\"iv\": \"00"),
+        ] {
+            if json.contains(from) {
+                assert_eq!(
+                    Keystore::from_json(&json.replace(from, to)),
+                    Err(BlsError::InvalidKeystore),
+                    "{}",
+                    to
+                );
+            }
+        }
+    }
+    assert_eq!(Keystore::from_json("{}"), Err(BlsError::InvalidKeystore));
+    assert_eq!(
+        Keystore::encrypt(
+            &sk,
+            "password",
+            "",
+            Kdf::Scrypt { n: 15, r: 8, p: 1 },
+            &mut rng
+        ),
+        Err(BlsError::InvalidKeystore)
+    );
+}
+
+fn test_threshold<G, S>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+{
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let msg = "this is the message";
+    let sk = SecretKey::<G, S>::keygen("this is the key");
+    let (tpk, shares) = split(&sk, 3, 5, &mut rng).unwrap();
+    assert_eq!(tpk.threshold(), 3);
+    assert_eq!(tpk.len(), 5);
+    assert_eq!(tpk.group_key(), sk.public_key());
+    for (i, share) in shares.iter().enumerate() {
+        assert_eq!(share.index() as usize, i + 1);
+        assert_eq!(share.group_key(), sk.public_key());
+        assert_eq!(tpk.share(share.index()), Some(share.public_key()));
+    }
+    assert_eq!(tpk.share(0), None);
+    assert_eq!(tpk.share(6), None);
+
+    let psigs: Vec<PartialSignature<G, S>> = shares.iter().map(|s| s.sign(msg)).collect();
+    for psig in &psigs {
+        assert!(tpk.verify_partial(psig, msg));
+        assert!(!tpk.verify_partial(psig, "not the message"));
+    }
+    // a partial signature checked against another share's key
+    let moved = PartialSignature::new(2, psigs[0].signature());
+    assert_eq!(
+        tpk.try_verify_partial(&moved, msg),
+        Err(BlsError::InvalidSignature)
+    );
+    assert_eq!(
+        tpk.try_verify_partial(&PartialSignature::new(6, psigs[0].signature()), msg),
+        Err(BlsError::SignerOutOfRange)
+    );
+
+    // every subset of at least threshold shares yields the signature made with sk itself
+    let expect = sk.sign(msg);
+    for subset in &[&[0, 1, 2][..], &[4, 2, 0], &[1, 3, 4], &[0, 1, 2, 3, 4]] {
+        let chosen: Vec<_> = subset.iter().map(|&i| psigs[i]).collect();
+        let sig = tpk.combine(&chosen).unwrap();
+        assert_eq!(sig, expect);
+        assert!(tpk.group_key().verify(msg, &sig));
+        assert_eq!(tpk.combine_verified(&chosen, msg), Ok(expect));
+    }
+
+    assert_eq!(
+        tpk.combine(&psigs[..2]).map(|_| ()),
+        Err(BlsError::TooFewShares)
+    );
+    assert_eq!(
+        tpk.combine(&[psigs[0], psigs[1], psigs[1]]).map(|_| ()),
+        Err(BlsError::DuplicateSigner)
+    );
+    assert_eq!(
+        tpk.combine(&[
+            psigs[0],
+            psigs[1],
+            PartialSignature::new(0, psigs[2].signature())
+        ])
+        .map(|_| ()),
+        Err(BlsError::SignerOutOfRange)
+    );
+    // a bad partial signature yields a bad signature
+    let bad = [psigs[0], psigs[1], shares[2].sign("not the message")];
+    assert!(!tpk.group_key().verify(msg, &tpk.combine(&bad).unwrap()));
+    assert_eq!(
+        tpk.combine_verified(&bad, msg).map(|_| ()),
+        Err(BlsError::InvalidSignature)
+    );
+
+    // 1-of-1 and n-of-n are allowed; other thresholds are not
+    for &(t, n) in &[(1, 1), (4, 4)] {
+        let (tpk, shares) = split(&sk, t, n, &mut rng).unwrap();
+        let psigs: Vec<_> = shares.iter().map(|s| s.sign(msg)).collect();
+        assert_eq!(tpk.combine(&psigs), Ok(expect));
+    }
+    for &(t, n) in &[(0, 3), (4, 3), (0, 0)] {
+        assert_eq!(
+            split(&sk, t, n, &mut rng).map(|_| ()),
+            Err(BlsError::InvalidThreshold)
+        );
+    }
+}
+
+#[test]
+fn test_threshold_g1() {
+    test_threshold::<G1, Basic>();
+    test_threshold::<G1, Aug>();
+    test_threshold::<G1, Pop>();
+}
+
+#[test]
+fn test_threshold_g2() {
+    test_threshold::<G2, Basic>();
+    test_threshold::<G2, Aug>();
+    test_threshold::<G2, Pop>();
+}
+
+// toy encryption for simulated participants: XOR with a keystream depending on both parties
+fn _dkg_cipher(dealer: u32, recipient: u32, data: &[u8]) -> Vec<u8> {
+    let key = Sha256::digest(&[dealer.to_be_bytes(), recipient.to_be_bytes()].concat()[..]);
+    data.iter()
+        .zip(key.iter().cycle())
+        .map(|(d, k)| d ^ k)
+        .collect()
+}
+
+// run distributed key generation among n simulated participants, of which the silent ones never
+// deal; tamper(share) may alter or drop a dealer's share, and answer(justification) may alter or
+// drop a dealer's justification
+fn _run_dkg<G, S, T, A>(
+    threshold: usize,
+    n: usize,
+    silent: &[u32],
+    mut tamper: T,
+    mut answer: A,
+) -> Vec<Dkg<G, S>>
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+    T: FnMut(EncryptedShare) -> Option<EncryptedShare>,
+    A: FnMut(Justification) -> Option<Justification>,
+{
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let mut parties: Vec<Dkg<G, S>> = (1..=n as u32)
+        .map(|i| Dkg::new(i, threshold, n, &mut rng).unwrap())
+        .collect();
+
+    let mut commitments = Vec::new();
+    let mut shares = Vec::new();
+    for p in parties.iter_mut() {
+        let dealer = p.index();
+        if !silent.contains(&dealer) {
+            let (c, s) = p.deal(|recipient, pt| _dkg_cipher(dealer, recipient, pt));
+            commitments.push(c);
+            shares.extend(s.into_iter().filter_map(&mut tamper));
+        }
+    }
+    for p in parties.iter_mut() {
+        for c in &commitments {
+            assert_eq!(p.receive_commitment(c), Ok(()));
+        }
+        let me = p.index();
+        for s in shares.iter().filter(|s| s.recipient() == me) {
+            let r = p.receive_share(s, |dealer, ct| Some(_dkg_cipher(dealer, me, ct)));
+            assert!(r == Ok(()) || r == Err(BlsError::InvalidShare));
+        }
+    }
+
+    let complaints: Vec<Complaint> = parties.iter_mut().flat_map(|p| p.complaints()).collect();
+    for p in parties.iter_mut() {
+        let me = p.index();
+        for c in complaints.iter().filter(|c| c.accuser() != me) {
+            assert_eq!(p.receive_complaint(c), Ok(()));
+        }
+    }
+    let justs: Vec<Justification> = parties
+        .iter_mut()
+        .filter(|p| !silent.contains(&p.index()))
+        .flat_map(|p| p.justifications())
+        .filter_map(&mut answer)
+        .collect();
+    for p in parties.iter_mut() {
+        let me = p.index();
+        for j in justs.iter().filter(|j| j.dealer() != me) {
+            let r = p.receive_justification(j);
+            assert!(r == Ok(()) || r == Err(BlsError::InvalidShare));
+        }
+    }
+    parties
+}
+
+// every participant but the cheaters agrees on the qualified set and the keys, and the shares of
+// signers (indices into parties) make a valid signature
+fn _check_dkg<G, S>(parties: &[Dkg<G, S>], qualified: &[u32], cheaters: &[u32], signers: &[usize])
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+{
+    let msg = "this is the message";
+    let honest: Vec<&Dkg<G, S>> = parties
+        .iter()
+        .filter(|p| !cheaters.contains(&p.index()))
+        .collect();
+    let outputs: Vec<_> = honest.iter().map(|p| p.finish().unwrap()).collect();
+    let tpk = &outputs[0].0;
+    for (p, (t, share)) in honest.iter().zip(outputs.iter()) {
+        assert_eq!(p.qualified(), qualified);
+        assert_eq!(t.group_key(), tpk.group_key());
+        assert_eq!(t.share(p.index()), Some(share.public_key()));
+        for i in 1..=(parties.len() as u32) {
+            assert_eq!(t.share(i), tpk.share(i));
+        }
+    }
+    let psigs: Vec<_> = signers.iter().map(|&i| outputs[i].1.sign(msg)).collect();
+    for psig in &psigs {
+        assert!(tpk.verify_partial(psig, msg));
+    }
+    let sig = tpk.combine(&psigs).unwrap();
+    assert!(tpk.group_key().verify(msg, &sig));
+}
+
+fn test_dkg<G, S>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+{
+    // everyone honest
+    let parties = _run_dkg::<G, S, _, _>(3, 5, &[], Some, Some);
+    _check_dkg(&parties, &[1, 2, 3, 4, 5], &[], &[0, 2, 4]);
+    _check_dkg(&parties, &[1, 2, 3, 4, 5], &[], &[3, 1, 2]);
+
+    // dealer 2 sends a bad share to 4 and none to 5, then answers both complaints
+    let tamper = |s: EncryptedShare| match (s.dealer(), s.recipient()) {
+        (2, 4) => Some(EncryptedShare::new(2, 4, vec![0u8; s.payload().len()])),
+        (2, 5) => None,
+        _ => Some(s),
+    };
+    let parties = _run_dkg::<G, S, _, _>(3, 5, &[], tamper, Some);
+    _check_dkg(&parties, &[1, 2, 3, 4, 5], &[], &[3, 4, 0]);
+
+    // dealer 2 ignores one complaint and dealer 3 answers with a wrong share; dealer 1 is silent
+    let answer = |j: Justification| match (j.dealer(), j.accuser()) {
+        (2, 5) => None,
+        (3, _) => Some(Justification::new(
+            3,
+            j.accuser(),
+            vec![1u8; j.share().len()],
+        )),
+        _ => Some(j),
+    };
+    let tamper = |s: EncryptedShare| match (s.dealer(), s.recipient()) {
+        (2, 4) | (2, 5) | (3, 1) => Some(EncryptedShare::new(s.dealer(), s.recipient(), vec![])),
+        _ => Some(s),
+    };
+    let parties = _run_dkg::<G, S, _, _>(2, 5, &[1], tamper, answer);
+    // the honest participants are 4 and 5
+    _check_dkg(&parties, &[4, 5], &[1, 2, 3], &[0, 1]);
+
+    // too few dealers qualify
+    let parties = _run_dkg::<G, S, _, _>(3, 4, &[1, 2], Some, Some);
+    assert_eq!(parties[0].qualified(), vec![3, 4]);
+    assert_eq!(
+        parties[0].finish().map(|_| ()),
+        Err(BlsError::TooFewDealers)
+    );
+}
+
+#[test]
+fn test_dkg_g1() {
+    test_dkg::<G1, Basic>();
+    test_dkg::<G1, Aug>();
+    test_dkg::<G1, Pop>();
+}
+
+#[test]
+fn test_dkg_g2() {
+    test_dkg::<G2, Basic>();
+    test_dkg::<G2, Aug>();
+    test_dkg::<G2, Pop>();
+}
+
+#[test]
+fn test_dkg_errors() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for &(i, t, n, err) in &[
+        (1, 0, 3, BlsError::InvalidThreshold),
+        (1, 4, 3, BlsError::InvalidThreshold),
+        (0, 2, 3, BlsError::SignerOutOfRange),
+        (4, 2, 3, BlsError::SignerOutOfRange),
+    ] {
+        assert_eq!(
+            Dkg::<G1, Basic>::new(i, t, n, &mut rng).map(|_| ()),
+            Err(err)
+        );
+    }
+
+    let mut one = Dkg::<G1, Basic>::new(1, 2, 3, &mut rng).unwrap();
+    let mut two = Dkg::<G1, Basic>::new(2, 2, 3, &mut rng).unwrap();
+    let plain = |_: u32, pt: &[u8]| pt.to_vec();
+    let open = |_: u32, ct: &[u8]| Some(ct.to_vec());
+    let (c1, s1) = one.deal(plain);
+    let (c2, _) = two.deal(plain);
+    assert_eq!(c1.dealer(), 1);
+    assert_eq!(c1.coefficients().len(), 2);
+    assert_eq!(s1.len(), 2);
+    assert_eq!(s1[0].recipient(), 2);
+    assert!(format!("{:?}", one).starts_with("Dkg { index: 1,"));
+
+    // a share before its commitment, or for someone else
+    assert_eq!(
+        two.receive_share(&s1[0], open),
+        Err(BlsError::InvalidDkgMessage)
+    );
+    assert_eq!(two.receive_commitment(&c1), Ok(()));
+    assert_eq!(two.receive_commitment(&c1), Ok(()));
+    assert_eq!(
+        two.receive_share(&s1[1], open),
+        Err(BlsError::InvalidDkgMessage)
+    );
+    // a share that does not decrypt, or that does not match the commitment
+    assert_eq!(
+        two.receive_share(&s1[0], |_, _| None),
+        Err(BlsError::InvalidShare)
+    );
+    assert_eq!(two.complaints(), vec![Complaint::new(1, 2)]);
+    assert_eq!(two.receive_share(&s1[0], open), Ok(()));
+    assert_eq!(two.complaints(), vec![]);
+
+    // commitments of the wrong length or that change, and out-of-range indices
+    let short = Commitment::new(1, c1.coefficients()[..1].to_vec());
+    assert_eq!(
+        two.receive_commitment(&short),
+        Err(BlsError::InvalidDkgMessage)
+    );
+    let changed = Commitment::new(1, c2.coefficients().to_vec());
+    assert_eq!(
+        two.receive_commitment(&changed),
+        Err(BlsError::InvalidDkgMessage)
+    );
+    assert_eq!(
+        two.receive_commitment(&Commitment::new(4, c2.coefficients().to_vec())),
+        Err(BlsError::SignerOutOfRange)
+    );
+    assert_eq!(
+        two.receive_complaint(&Complaint::new(1, 1)),
+        Err(BlsError::InvalidDkgMessage)
+    );
+    assert_eq!(
+        two.receive_complaint(&Complaint::new(0, 1)),
+        Err(BlsError::SignerOutOfRange)
+    );
+    // a justification with no complaint
+    assert_eq!(
+        two.receive_justification(&Justification::new(1, 3, vec![0u8; 32])),
+        Err(BlsError::InvalidDkgMessage)
+    );
+}
+
+fn test_prepared<G, S>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>>,
+    S: Scheme<G, ExpandMsgXmd<Sha256>>,
+{
+    let msg = "this is the message";
+    let sk = SecretKey::<G, S>::keygen("this is the key");
+    let pk = sk.public_key();
+    let prepared = pk.prepare().unwrap();
+    assert_eq!(prepared.public_key(), pk);
+    let sig = sk.sign(msg);
+    for _ in 0..2 {
+        assert!(prepared.verify(msg, &sig));
+    }
+    let other = SecretKey::<G, S>::keygen("this is another key").sign(msg);
+    for &(m, s) in &[(msg, &other), ("not the message", &sig)] {
+        assert_eq!(s.try_verify(&pk, m), Err(BlsError::InvalidSignature));
+        assert_eq!(prepared.try_verify(m, s), Err(BlsError::InvalidSignature));
+    }
+    assert_eq!(
+        PreparedPublicKey::new(&PublicKey::<G, S>::new(
+            <G::PKType as CurveProjective>::zero()
+        ))
+        .map(|_| ()),
+        Err(BlsError::IdentityKey)
+    );
+
+    // the core function takes the prepared point itself
+    let p = pk.point().into_affine().prepare();
+    let core_sig = G::core_sign(sk.x_prime(), msg, b"prepared test");
+    assert!(G::core_verify_prepared(&p, core_sig, msg, b"prepared test"));
+    assert!(!G::core_verify_prepared(&p, core_sig, msg, b"another test"));
+}
+
+#[test]
+fn test_prepared_g1() {
+    test_prepared::<G1, Basic>();
+    test_prepared::<G1, Aug>();
+    test_prepared::<G1, Pop>();
+}
+
+#[test]
+fn test_prepared_g2() {
+    test_prepared::<G2, Basic>();
+    test_prepared::<G2, Aug>();
+    test_prepared::<G2, Pop>();
+}
+
+fn test_hashed<G, S>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>>,
+    S: PrehashScheme<G, ExpandMsgXmd<Sha256>>,
+{
+    let msg: &[u8] = b"this is the message";
+    let hashed = HashedMessage::<G, S>::new(msg);
+    assert_eq!(hashed.point(), G::hash_message(msg, S::CSUITE));
+    let sk = SecretKey::<G, S>::keygen("this is the key");
+    let pk = sk.public_key();
+    let prepared = pk.prepare().unwrap();
+    let sig = sk.sign_hashed(&hashed);
+    assert_eq!(sig, sk.sign(msg));
+    assert!(sig.verify_hashed(&pk, &hashed));
+    assert!(pk.verify_hashed(&hashed, &sig));
+    assert!(prepared.verify_hashed(&hashed, &sig));
+
+    let other = HashedMessage::new("not the message");
+    assert_eq!(
+        sig.try_verify_hashed(&pk, &other),
+        Err(BlsError::InvalidSignature)
+    );
+    assert_eq!(
+        prepared.try_verify_hashed(&other, &sig),
+        Err(BlsError::InvalidSignature)
+    );
+    assert_eq!(
+        sig.try_verify_hashed(
+            &PublicKey::new(<G::PKType as CurveProjective>::zero()),
+            &hashed
+        ),
+        Err(BlsError::IdentityKey)
+    );
+}
+
+fn test_grouping<G>()
+where
+    G: BLSSignatureBasic<ExpandMsgXmd<Sha256>> + BLSSignaturePop<ExpandMsgXmd<Sha256>>,
+{
+    // three signers on one message, two on another, and one alone
+    let msgs: [&[u8]; 6] = [b"one", b"two", b"one", b"three", b"two", b"one"];
+    let hashes: Vec<HashedMessage<G, Pop>> = msgs.iter().map(|m| HashedMessage::new(m)).collect();
+    let sks: Vec<SecretKey<G, Pop>> = (0..6u8).map(|i| SecretKey::keygen([i; 32])).collect();
+    let pks: Vec<_> = sks.iter().map(|sk| sk.public_key()).collect();
+    let sigs: Vec<_> = sks.iter().zip(&msgs).map(|(sk, m)| sk.sign(m)).collect();
+    let agg = Signature::aggregate(&sigs[..]);
+    assert!(agg.aggregate_verify(&pks[..], &msgs[..]));
+    assert!(agg.aggregate_verify_hashed(&pks[..], &hashes[..]));
+
+    // keys on the same message can trade places, but keys on different messages cannot
+    let mut swapped = pks.clone();
+    swapped.swap(0, 2);
+    assert!(agg.aggregate_verify(&swapped[..], &msgs[..]));
+    assert!(agg.aggregate_verify_hashed(&swapped[..], &hashes[..]));
+    swapped.swap(0, 1);
+    assert_eq!(
+        agg.try_aggregate_verify(&swapped[..], &msgs[..]),
+        Err(BlsError::InvalidSignature)
+    );
+    assert_eq!(
+        agg.try_aggregate_verify_hashed(&swapped[..], &hashes[..]),
+        Err(BlsError::InvalidSignature)
+    );
+    assert_eq!(
+        agg.try_aggregate_verify_hashed(&pks[..2], &hashes[..]),
+        Err(BlsError::LengthMismatch)
+    );
+
+    #[cfg(feature = "parallel")]
+    {
+        let points: Vec<G::PKType> = pks.iter().map(|pk| pk.point()).collect();
+        let csuite = <G as BLSSignaturePop<ExpandMsgXmd<Sha256>>>::CSUITE;
+        assert!(G::core_aggregate_verify_parallel(
+            &points[..],
+            &msgs[..],
+            agg.point(),
+            csuite
+        ));
+        assert!(!G::core_aggregate_verify_parallel(
+            &points[1..],
+            &msgs[..5],
+            agg.point(),
+            csuite
+        ));
+    }
+
+    // a multisignature on a message hashed once
+    let hashed = &hashes[0];
+    let multisig = Signature::aggregate(
+        &sks.iter()
+            .map(|sk| sk.sign_hashed(hashed))
+            .collect::<Vec<_>>()[..],
+    );
+    assert!(multisig.multisig_verify_hashed(&pks[..], hashed));
+    assert!(!multisig.multisig_verify_hashed(&pks[1..], hashed));
+    assert!(!multisig.multisig_verify_hashed(&pks[..], &hashes[1]));
+
+    // Basic still requires distinct messages
+    let hashes: Vec<HashedMessage<G, Basic>> = msgs.iter().map(|m| HashedMessage::new(m)).collect();
+    let sks: Vec<SecretKey<G, Basic>> = (0..6u8).map(|i| SecretKey::keygen([i; 32])).collect();
+    let pks: Vec<_> = sks.iter().map(|sk| sk.public_key()).collect();
+    let sigs: Vec<_> = sks.iter().zip(&msgs).map(|(sk, m)| sk.sign(m)).collect();
+    let agg = Signature::aggregate(&sigs[..2]);
+    assert!(agg.aggregate_verify_hashed(&pks[..2], &hashes[..2]));
+    let agg = Signature::aggregate(&sigs[..3]);
+    assert_eq!(
+        agg.try_aggregate_verify_hashed(&pks[..3], &hashes[..3]),
+        Err(BlsError::DuplicateMessage)
+    );
+}
+
+#[test]
+fn test_hashed_g1() {
+    test_hashed::<G1, Basic>();
+    test_hashed::<G1, Pop>();
+    test_grouping::<G1>();
+}
+
+#[test]
+fn test_hashed_g2() {
+    test_hashed::<G2, Basic>();
+    test_hashed::<G2, Pop>();
+    test_grouping::<G2>();
+}
+
+fn test_stream<G, X>()
+where
+    G: BLSSignatureBasic<X> + BLSSignaturePop<X> + FromUniformBytes,
+    X: ExpandMsgStream,
+{
+    // several hash blocks long
+    let msg: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+
+    // the core function, with a custom ciphersuite and uneven pieces
+    let csuite = b"stream test";
+    for m in &[&msg[..], &msg[..1], &[]] {
+        let mut state = X::start();
+        for chunk in m.chunks(300) {
+            X::absorb(&mut state, chunk);
+        }
+        assert_eq!(
+            hash_message_stream::<G, X>(state, csuite),
+            G::hash_message(m, csuite)
+        );
+    }
+
+    // an oversize DST is hashed first, matching a CustomScheme with the same DST
+    let long_dst = [0x5a; 256];
+    let scheme = SchemeBuilder::<G, Basic, X>::new()
+        .dst(&long_dst[..])
+        .build()
+        .unwrap();
+    let mut state = X::start();
+    X::absorb(&mut state, &msg[..]);
+    let hash = hash_message_stream::<G, X>(state, &long_dst[..]);
+    assert_eq!(hash, G::hash_message(&msg[..], scheme.dst()));
+    let sk = SecretKey::<G, Basic, X>::keygen("this is the key");
+    assert_eq!(
+        scheme.sign(&sk, &msg[..]).point(),
+        G::core_sign_hashed(sk.x_prime(), hash)
+    );
+
+    // the typed API, in pieces and from a reader
+    let mut stream = MessageStream::<G, Basic, X>::new();
+    stream.update(&msg[..500]);
+    let mut copy = stream.clone();
+    stream.update(&msg[500..]);
+    let hashed = stream.finish();
+    assert_eq!(hashed, HashedMessage::new(&msg[..]));
+    copy.update(&msg[500..999]);
+    assert_ne!(copy.finish(), hashed);
+    let hashed = HashedMessage::<G, Pop, X>::from_reader(&mut Cursor::new(&msg[..])).unwrap();
+    assert_eq!(hashed, HashedMessage::new(&msg[..]));
+
+    let mut stream = MessageStream::<G, Pop, X>::new();
+    io::copy(&mut &msg[..], &mut stream).unwrap();
+    let hashed = stream.finish();
+    let sk = SecretKey::<G, Pop, X>::keygen("this is the key");
+    let sig = sk.sign_hashed(&hashed);
+    assert_eq!(sig, sk.sign(&msg[..]));
+    assert!(sig.verify(&sk.public_key(), &msg[..]));
+    assert!(sk.public_key().verify_hashed(&hashed, &sk.sign(&msg[..])));
+}
+
+#[test]
+fn test_stream_g1() {
+    test_stream::<G1, ExpandMsgXmd<Sha256>>();
+    test_stream::<G1, ExpandMsgXmd<Sha512>>();
+    test_stream::<G1, ExpandMsgXof<Shake128>>();
+    test_stream::<G1, ExpandMsgXof<Shake256>>();
+}
+
+#[test]
+fn test_stream_g2() {
+    test_stream::<G2, ExpandMsgXmd<Sha256>>();
+    test_stream::<G2, ExpandMsgXmd<Sha512>>();
+    test_stream::<G2, ExpandMsgXof<Shake128>>();
+    test_stream::<G2, ExpandMsgXof<Shake256>>();
+}
+
+#[cfg(feature = "parallel")]
+fn test_parallel<G>()
+where
+    G: BLSSigCore<ExpandMsgXmd<Sha256>> + CurveProjective<Scalar = Fr>,
+{
+    let csuite = b"parallel test";
+    // enough messages for several chunks, the last one short
+    let msgs: Vec<Vec<u8>> = (0..70u32).map(|i| i.to_be_bytes().to_vec()).collect();
+    let keys: Vec<_> = msgs.iter().map(|m| G::keygen(m)).collect();
+    let pks: Vec<G::PKType> = keys.iter().map(|&(_, pk)| pk).collect();
+    let mut sigs: Vec<G> = keys
+        .iter()
+        .zip(&msgs)
+        .map(|(&(x_prime, _), m)| G::core_sign(x_prime, m, csuite))
+        .collect();
+    let agg = G::aggregate(&sigs[..]);
+    let seed = [
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ];
+    let check = |pks: &[G::PKType], msgs: &[Vec<u8>], agg: G, sigs: &[G]| {
+        let serial = G::try_core_aggregate_verify(pks, msgs, agg, csuite);
+        assert_eq!(
+            G::try_core_aggregate_verify_parallel(pks, msgs, agg, csuite),
+            serial
+        );
+        let serial =
+            G::try_core_batch_verify(pks, msgs, sigs, csuite, &mut XorShiftRng::from_seed(seed));
+        assert_eq!(
+            G::try_core_batch_verify_parallel(
+                pks,
+                msgs,
+                sigs,
+                csuite,
+                &mut XorShiftRng::from_seed(seed)
+            ),
+            serial
+        );
+        serial
+    };
+
+    assert_eq!(check(&pks[..], &msgs[..], agg, &sigs[..]), Ok(()));
+    assert_eq!(check(&pks[..1], &msgs[..1], sigs[0], &sigs[..1]), Ok(()));
+    assert!(G::core_aggregate_verify_parallel(
+        &pks[..],
+        &msgs[..],
+        agg,
+        csuite
+    ));
+
+    // a wrong message in the last chunk, or a wrong signature in the first
+    let mut bad_msgs = msgs.clone();
+    bad_msgs[69] = b"not the message".to_vec();
+    assert_eq!(
+        check(&pks[..], &bad_msgs[..], agg, &sigs[..]),
+        Err(BlsError::InvalidSignature)
+    );
+    sigs[3] = sigs[4];
+    assert_eq!(
+        check(&pks[..], &msgs[..], G::aggregate(&sigs[..]), &sigs[..]),
+        Err(BlsError::InvalidSignature)
+    );
+
+    assert_eq!(
+        G::try_core_aggregate_verify_parallel(&pks[..3], &msgs[..], agg, csuite),
+        Err(BlsError::LengthMismatch)
+    );
+    assert_eq!(
+        G::try_core_aggregate_verify_parallel(&pks[..0], &msgs[..0], agg, csuite),
+        Err(BlsError::EmptyInput)
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_g1() {
+    test_parallel::<G1>();
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_g2() {
+    test_parallel::<G2>();
+}
@@ -0,0 +1,373 @@
+/*!
+Threshold signatures with a trusted dealer
+
+A dealer splits a secret key x into n shares with a random polynomial f of degree t - 1 over Fr,
+where f(0) = x: share i (for i = 1..n) is f(i), and its public key is g^f(i). Each share holder
+signs on its own, producing a partial signature H(m)^f(i). Any t valid partial signatures combine,
+by Lagrange interpolation at 0 in the exponent, into H(m)^x: an ordinary signature under the
+group public key g^x, indistinguishable from one made with x directly.
+
+For the Aug scheme, every share signs the message augmented with the group public key, so that
+the combined signature verifies under it.
+
+Combining does not check the partial signatures: an invalid one yields an invalid signature.
+Verify each partial signature against its share's public key first, or verify the result.
+*/
+
+use error::BlsError;
+use ff::{Field, PrimeField};
+use pairing_plus::bls12_381::{Fr, FrRepr};
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd};
+use pairing_plus::CurveProjective;
+use rand_core::RngCore;
+use sha2::Sha256;
+use signature::{_wipe_scalar, BLSSigCore};
+use std::collections::HashSet;
+use std::fmt;
+use types::{PublicKey, Scheme, SecretKey, Signature};
+
+/// One holder's share of a threshold secret key
+pub struct SecretKeyShare<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    index: u32,
+    sk: SecretKey<G, S, X>,
+    group_key: PublicKey<G, S, X>,
+}
+
+/// The public side of a threshold key: the group public key and the public key of every share
+pub struct ThresholdPublicKey<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    threshold: usize,
+    group_key: PublicKey<G, S, X>,
+    shares: Vec<PublicKey<G, S, X>>,
+}
+
+/// A signature made with one share, tagged with the share's index
+pub struct PartialSignature<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    index: u32,
+    sig: Signature<G, S, X>,
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for SecretKeyShare<G, S, X> {
+    fn clone(&self) -> Self {
+        SecretKeyShare {
+            index: self.index,
+            sk: self.sk.clone(),
+            group_key: self.group_key,
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for SecretKeyShare<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SecretKeyShare")
+            .field("index", &self.index)
+            .field("sk", &self.sk)
+            .field("group_key", &self.group_key)
+            .finish()
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for ThresholdPublicKey<G, S, X> {
+    fn clone(&self) -> Self {
+        ThresholdPublicKey {
+            threshold: self.threshold,
+            group_key: self.group_key,
+            shares: self.shares.clone(),
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for ThresholdPublicKey<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThresholdPublicKey")
+            .field("threshold", &self.threshold)
+            .field("group_key", &self.group_key)
+            .field("shares", &self.shares)
+            .finish()
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for PartialSignature<G, S, X> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Copy for PartialSignature<G, S, X> {}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for PartialSignature<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PartialSignature")
+            .field("index", &self.index)
+            .field("sig", &self.sig)
+            .finish()
+    }
+}
+
+/// The result of splitting a key: the public side and one share per holder
+pub type SplitKey<G, S, X> = (ThresholdPublicKey<G, S, X>, Vec<SecretKeyShare<G, S, X>>);
+
+/// Split sk into n shares, any threshold of which can sign; the dealer should then discard sk
+///
+/// Shares are numbered 1 through n. This fails with InvalidThreshold unless
+/// 1 <= threshold <= n < 2^32.
+pub fn split<G, S, X, R>(
+    sk: &SecretKey<G, S, X>,
+    threshold: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<SplitKey<G, S, X>, BlsError>
+where
+    G: BLSSigCore<X> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, X>,
+    X: ExpandMsg,
+    R: RngCore,
+{
+    if threshold == 0 || threshold > n || n > u32::MAX as usize {
+        return Err(BlsError::InvalidThreshold);
+    }
+
+    // f(x) = sk + c_1 x + ... + c_{t-1} x^{t-1}
+    let mut coeffs = Vec::with_capacity(threshold);
+    coeffs.push(sk.x_prime());
+    for _ in 1..threshold {
+        coeffs.push(Fr::random(rng));
+    }
+
+    let group_key = sk.public_key();
+    let mut public_shares = Vec::with_capacity(n);
+    let mut shares = Vec::with_capacity(n);
+    for index in 1..=(n as u32) {
+        // Horner's rule
+        let x = _index_to_fr(index);
+        let mut y = Fr::zero();
+        for c in coeffs.iter().rev() {
+            y.mul_assign(&x);
+            y.add_assign(c);
+        }
+        let share = SecretKey::new(y);
+        _wipe_scalar(&mut y);
+        public_shares.push(share.public_key());
+        shares.push(SecretKeyShare {
+            index,
+            sk: share,
+            group_key,
+        });
+    }
+    for c in coeffs.iter_mut() {
+        _wipe_scalar(c);
+    }
+
+    Ok((
+        ThresholdPublicKey {
+            threshold,
+            group_key,
+            shares: public_shares,
+        },
+        shares,
+    ))
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> SecretKeyShare<G, S, X> {
+    // a share produced elsewhere, e.g., by distributed key generation
+    pub(crate) fn from_parts(
+        index: u32,
+        sk: SecretKey<G, S, X>,
+        group_key: PublicKey<G, S, X>,
+    ) -> Self {
+        SecretKeyShare {
+            index,
+            sk,
+            group_key,
+        }
+    }
+
+    /// This share's index, between 1 and the number of shares
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The public key of the whole group
+    pub fn group_key(&self) -> PublicKey<G, S, X> {
+        self.group_key
+    }
+
+    /// The public key of this share
+    pub fn public_key(&self) -> PublicKey<G, S, X> {
+        self.sk.public_key()
+    }
+
+    /// Sign a message with this share
+    pub fn sign<B: AsRef<[u8]>>(&self, msg: B) -> PartialSignature<G, S, X> {
+        // core_sign wipes its copy of the scalar
+        let sig = <G as BLSSigCore<X>>::core_sign(
+            self.sk.x_prime(),
+            S::core_message(&self.group_key.point(), msg),
+            S::CSUITE,
+        );
+        PartialSignature {
+            index: self.index,
+            sig: Signature::new(sig),
+        }
+    }
+}
+
+impl<G, S, X> ThresholdPublicKey<G, S, X>
+where
+    G: BLSSigCore<X> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, X>,
+    X: ExpandMsg,
+{
+    // the public side of shares produced elsewhere; shares[i] is the key of share i + 1
+    pub(crate) fn from_parts(
+        threshold: usize,
+        group_key: PublicKey<G, S, X>,
+        shares: Vec<PublicKey<G, S, X>>,
+    ) -> Self {
+        ThresholdPublicKey {
+            threshold,
+            group_key,
+            shares,
+        }
+    }
+
+    /// The number of partial signatures needed to sign
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The number of shares
+    pub fn len(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Always false: a threshold key has at least one share
+    pub fn is_empty(&self) -> bool {
+        self.shares.is_empty()
+    }
+
+    /// The public key of the whole group, under which combined signatures verify
+    pub fn group_key(&self) -> PublicKey<G, S, X> {
+        self.group_key
+    }
+
+    /// The public key of share index, if there is such a share
+    pub fn share(&self, index: u32) -> Option<PublicKey<G, S, X>> {
+        (index as usize)
+            .checked_sub(1)
+            .and_then(|i| self.shares.get(i))
+            .cloned()
+    }
+
+    /// Verify a partial signature against its share's public key
+    pub fn verify_partial<B: AsRef<[u8]>>(&self, psig: &PartialSignature<G, S, X>, msg: B) -> bool {
+        self.try_verify_partial(psig, msg).is_ok()
+    }
+
+    /// like verify_partial, but returns the reason for rejection
+    pub fn try_verify_partial<B: AsRef<[u8]>>(
+        &self,
+        psig: &PartialSignature<G, S, X>,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        let pk = self.share(psig.index).ok_or(BlsError::SignerOutOfRange)?;
+        <G as BLSSigCore<X>>::try_core_verify(
+            pk.point(),
+            psig.sig.point(),
+            S::core_message(&self.group_key.point(), msg),
+            S::CSUITE,
+        )
+    }
+
+    /// Combine partial signatures from at least threshold distinct shares into a signature
+    /// under the group public key
+    ///
+    /// This does not check the partial signatures (see the module documentation).
+    pub fn combine(
+        &self,
+        psigs: &[PartialSignature<G, S, X>],
+    ) -> Result<Signature<G, S, X>, BlsError> {
+        let mut seen = HashSet::with_capacity(psigs.len());
+        for psig in psigs {
+            if self.share(psig.index).is_none() {
+                return Err(BlsError::SignerOutOfRange);
+            }
+            if !seen.insert(psig.index) {
+                return Err(BlsError::DuplicateSigner);
+            }
+        }
+        if psigs.len() < self.threshold {
+            return Err(BlsError::TooFewShares);
+        }
+
+        let indices: Vec<Fr> = psigs.iter().map(|p| _index_to_fr(p.index)).collect();
+        let mut sig = G::zero();
+        for (i, psig) in psigs.iter().enumerate() {
+            let mut term = psig.sig.point();
+            term.mul_assign(_lagrange_at_zero(&indices, i));
+            sig.add_assign(&term);
+        }
+        Ok(Signature::new(sig))
+    }
+
+    /// Combine partial signatures, then verify the result under the group public key
+    pub fn combine_verified<B: AsRef<[u8]>>(
+        &self,
+        psigs: &[PartialSignature<G, S, X>],
+        msg: B,
+    ) -> Result<Signature<G, S, X>, BlsError> {
+        let sig = self.combine(psigs)?;
+        S::try_verify(self.group_key.point(), sig.point(), msg)?;
+        Ok(sig)
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> PartialSignature<G, S, X> {
+    /// Tag a signature with the index of the share that made it, e.g., after receiving both
+    pub fn new(index: u32, sig: Signature<G, S, X>) -> Self {
+        PartialSignature { index, sig }
+    }
+
+    /// The index of the share that made this signature
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The signature itself
+    pub fn signature(&self) -> Signature<G, S, X> {
+        self.sig
+    }
+}
+
+// a share's index as the point at which the polynomial is evaluated
+pub(crate) fn _index_to_fr(index: u32) -> Fr {
+    Fr::from_repr(FrRepr::from(u64::from(index))).expect("indices are less than the group order")
+}
+
+// the Lagrange coefficient of the i'th point for interpolation at 0: prod_{j != i} x_j / (x_j - x_i)
+fn _lagrange_at_zero(xs: &[Fr], i: usize) -> Fr {
+    let mut num = Fr::one();
+    let mut den = Fr::one();
+    for (j, xj) in xs.iter().enumerate() {
+        if j != i {
+            num.mul_assign(xj);
+            let mut diff = *xj;
+            diff.sub_assign(&xs[i]);
+            den.mul_assign(&diff);
+        }
+    }
+    // the indices are distinct, so den is nonzero
+    num.mul_assign(&den.inverse().expect("indices are distinct"));
+    num
+}
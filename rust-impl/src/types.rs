@@ -0,0 +1,949 @@
+/*!
+Typed secret keys, public keys, and signatures
+
+The wrappers in this module are parameterized by the signature group `G` (i.e., `G1` or `G2`),
+the scheme `S` (one of `Basic`, `Aug`, or `Pop`), and the message expander `X`. Passing a public
+key where a signature is expected, or mixing keys and signatures from different schemes, is a
+compile error rather than a failed verification.
+*/
+
+use ct::ct_mul_assign;
+use derive::{derive_child_sk, derive_master_sk, derive_path};
+use encoding::PointEncoding;
+use error::BlsError;
+use ff::{Field, PrimeField, PrimeFieldRepr};
+use pairing_plus::bls12_381::Fr;
+use pairing_plus::hash_to_field::{ExpandMsg, ExpandMsgXmd};
+use pairing_plus::{CurveAffine, CurveProjective};
+use rand_core::RngCore;
+use sha2::Sha256;
+use signature::{
+    _aug_aggregate_verify, _aug_sign, _aug_verify, _augment, _basic_aggregate_verify,
+    _basic_aggregate_verify_hashed, _check_agg_inputs, _keys_validate, _pop_prove, _wipe_scalar,
+    BLSSigCore, BLSSignatureAug, BLSSignatureBasic, BLSSignaturePop, PKPrepared,
+};
+use std::fmt;
+use std::marker::PhantomData;
+use zeroize::Zeroize;
+
+/// Alias for the scalar type corresponding to a CurveProjective type
+type ScalarT<PtT> = <PtT as CurveProjective>::Scalar;
+
+/// Alias for the public key type corresponding to a signature type
+pub(crate) type PKType<G, X> = <G as BLSSigCore<X>>::PKType;
+
+// zero-sized marker for types parameterized by group, scheme, and expander
+pub(crate) type SchemeMarker<G, S, X> = PhantomData<fn() -> (G, S, X)>;
+
+/// Marker for the 'Basic' scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Basic;
+
+/// Marker for the message augmentation scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Aug;
+
+/// Marker for the proof of possession scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pop;
+
+/// A BLS signature scheme over the signature group G
+pub trait Scheme<G: BLSSigCore<X>, X: ExpandMsg> {
+    /// Ciphersuite tag
+    const CSUITE: &'static [u8];
+
+    /// Does this scheme use proofs of possession?
+    const POP: bool = false;
+
+    /// Sign a message
+    fn sign<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B) -> G;
+
+    /// The input to CoreSign when the holder of pk signs msg: msg itself, or pk || msg for Aug
+    fn core_message<B: AsRef<[u8]>>(pk: &PKType<G, X>, msg: B) -> Vec<u8>;
+
+    /// Sign a message under the domain separation tag dst instead of CSUITE
+    fn sign_with_dst<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B, dst: &[u8]) -> G;
+
+    /// Verify a signature under the domain separation tag dst instead of CSUITE
+    fn try_verify_with_dst<B: AsRef<[u8]>>(
+        pk: PKType<G, X>,
+        sig: G,
+        msg: B,
+        dst: &[u8],
+    ) -> Result<(), BlsError>;
+
+    /// Verify an aggregated signature under the domain separation tag dst instead of CSUITE
+    fn try_aggregate_verify_with_dst<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+        dst: &[u8],
+    ) -> Result<(), BlsError>;
+
+    /// Verify a signature, returning the reason for rejection
+    fn try_verify<B: AsRef<[u8]>>(pk: PKType<G, X>, sig: G, msg: B) -> Result<(), BlsError>;
+
+    /// like try_verify, for a key that was validated and prepared in advance (skips KeyValidate)
+    fn try_verify_prepared<B: AsRef<[u8]>>(
+        pk: &PKType<G, X>,
+        prepared: &PKPrepared<G, X>,
+        sig: G,
+        msg: B,
+    ) -> Result<(), BlsError>;
+
+    /// Verify an aggregated signature, returning the reason for rejection
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+    ) -> Result<(), BlsError>;
+
+    /// Verify many independent signatures at once, returning the reason for rejection
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sigs: &[G],
+        rng: &mut R,
+    ) -> Result<(), BlsError>;
+}
+
+/// A scheme whose messages can be hashed before the signer is known (see HashedMessage)
+pub trait PrehashScheme<G: BLSSigCore<X>, X: ExpandMsg>: Scheme<G, X> {}
+
+impl<G: BLSSignatureBasic<X>, X: ExpandMsg> PrehashScheme<G, X> for Basic {}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> PrehashScheme<G, X> for Pop {}
+
+impl<G: BLSSignatureBasic<X>, X: ExpandMsg> Scheme<G, X> for Basic {
+    const CSUITE: &'static [u8] = <G as BLSSignatureBasic<X>>::CSUITE;
+
+    fn sign<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B) -> G {
+        <G as BLSSignatureBasic<X>>::sign(x_prime, msg)
+    }
+
+    fn core_message<B: AsRef<[u8]>>(_pk: &PKType<G, X>, msg: B) -> Vec<u8> {
+        msg.as_ref().to_vec()
+    }
+
+    fn sign_with_dst<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B, dst: &[u8]) -> G {
+        <G as BLSSigCore<X>>::core_sign(x_prime, msg, dst)
+    }
+
+    fn try_verify_with_dst<B: AsRef<[u8]>>(
+        pk: PKType<G, X>,
+        sig: G,
+        msg: B,
+        dst: &[u8],
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_key_validate(&pk)?;
+        <G as BLSSigCore<X>>::try_core_verify(pk, sig, msg, dst)
+    }
+
+    fn try_aggregate_verify_with_dst<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+        dst: &[u8],
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        _keys_validate::<G, X>(pks)?;
+        _basic_aggregate_verify(pks, msgs, sig, dst)
+    }
+
+    fn try_verify<B: AsRef<[u8]>>(pk: PKType<G, X>, sig: G, msg: B) -> Result<(), BlsError> {
+        <G as BLSSignatureBasic<X>>::try_verify(pk, sig, msg)
+    }
+
+    fn try_verify_prepared<B: AsRef<[u8]>>(
+        _pk: &PKType<G, X>,
+        prepared: &PKPrepared<G, X>,
+        sig: G,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_core_verify_prepared(
+            prepared,
+            sig,
+            msg,
+            <G as BLSSignatureBasic<X>>::CSUITE,
+        )
+    }
+
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignatureBasic<X>>::try_aggregate_verify(pks, msgs, sig)
+    }
+
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sigs: &[G],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignatureBasic<X>>::try_batch_verify(pks, msgs, sigs, rng)
+    }
+}
+
+impl<G: BLSSignatureAug<X>, X: ExpandMsg> Scheme<G, X> for Aug {
+    const CSUITE: &'static [u8] = <G as BLSSignatureAug<X>>::CSUITE;
+
+    fn sign<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B) -> G {
+        <G as BLSSignatureAug<X>>::sign(x_prime, msg)
+    }
+
+    fn core_message<B: AsRef<[u8]>>(pk: &PKType<G, X>, msg: B) -> Vec<u8> {
+        _augment::<G, X, _>(pk, msg)
+    }
+
+    fn sign_with_dst<B: AsRef<[u8]>>(mut x_prime: ScalarT<G>, msg: B, dst: &[u8]) -> G {
+        let sig = _aug_sign::<G, X, _>(&x_prime, msg, dst);
+        _wipe_scalar(&mut x_prime);
+        sig
+    }
+
+    fn try_verify_with_dst<B: AsRef<[u8]>>(
+        pk: PKType<G, X>,
+        sig: G,
+        msg: B,
+        dst: &[u8],
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_key_validate(&pk)?;
+        _aug_verify::<G, X, _>(pk, sig, msg, dst)
+    }
+
+    fn try_aggregate_verify_with_dst<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+        dst: &[u8],
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        _keys_validate::<G, X>(pks)?;
+        _aug_aggregate_verify::<G, X, _>(pks, msgs, sig, dst)
+    }
+
+    fn try_verify<B: AsRef<[u8]>>(pk: PKType<G, X>, sig: G, msg: B) -> Result<(), BlsError> {
+        <G as BLSSignatureAug<X>>::try_verify(pk, sig, msg)
+    }
+
+    fn try_verify_prepared<B: AsRef<[u8]>>(
+        pk: &PKType<G, X>,
+        prepared: &PKPrepared<G, X>,
+        sig: G,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_core_verify_prepared(
+            prepared,
+            sig,
+            _augment::<G, X, _>(pk, msg),
+            <G as BLSSignatureAug<X>>::CSUITE,
+        )
+    }
+
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignatureAug<X>>::try_aggregate_verify(pks, msgs, sig)
+    }
+
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sigs: &[G],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignatureAug<X>>::try_batch_verify(pks, msgs, sigs, rng)
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> Scheme<G, X> for Pop {
+    const CSUITE: &'static [u8] = <G as BLSSignaturePop<X>>::CSUITE;
+    const POP: bool = true;
+
+    fn sign<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B) -> G {
+        <G as BLSSignaturePop<X>>::sign(x_prime, msg)
+    }
+
+    fn core_message<B: AsRef<[u8]>>(_pk: &PKType<G, X>, msg: B) -> Vec<u8> {
+        msg.as_ref().to_vec()
+    }
+
+    fn sign_with_dst<B: AsRef<[u8]>>(x_prime: ScalarT<G>, msg: B, dst: &[u8]) -> G {
+        <G as BLSSigCore<X>>::core_sign(x_prime, msg, dst)
+    }
+
+    fn try_verify_with_dst<B: AsRef<[u8]>>(
+        pk: PKType<G, X>,
+        sig: G,
+        msg: B,
+        dst: &[u8],
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_key_validate(&pk)?;
+        <G as BLSSigCore<X>>::try_core_verify(pk, sig, msg, dst)
+    }
+
+    fn try_aggregate_verify_with_dst<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+        dst: &[u8],
+    ) -> Result<(), BlsError> {
+        _check_agg_inputs(pks.len(), msgs.len())?;
+        _keys_validate::<G, X>(pks)?;
+        <G as BLSSigCore<X>>::try_core_aggregate_verify(pks, msgs, sig, dst)
+    }
+
+    fn try_verify<B: AsRef<[u8]>>(pk: PKType<G, X>, sig: G, msg: B) -> Result<(), BlsError> {
+        <G as BLSSignaturePop<X>>::try_verify(pk, sig, msg)
+    }
+
+    fn try_verify_prepared<B: AsRef<[u8]>>(
+        _pk: &PKType<G, X>,
+        prepared: &PKPrepared<G, X>,
+        sig: G,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_core_verify_prepared(
+            prepared,
+            sig,
+            msg,
+            <G as BLSSignaturePop<X>>::CSUITE,
+        )
+    }
+
+    fn try_aggregate_verify<B: AsRef<[u8]>>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sig: G,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignaturePop<X>>::try_aggregate_verify(pks, msgs, sig)
+    }
+
+    fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[PKType<G, X>],
+        msgs: &[B],
+        sigs: &[G],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignaturePop<X>>::try_batch_verify(pks, msgs, sigs, rng)
+    }
+}
+
+/// A secret key for scheme S with signatures in G
+pub struct SecretKey<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    x_prime: ScalarT<G>,
+    _scheme: PhantomData<fn() -> (S, X)>,
+}
+
+/// A public key for scheme S with signatures in G
+pub struct PublicKey<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    point: PKType<G, X>,
+    _scheme: PhantomData<fn() -> (S, X)>,
+}
+
+/// A public key for scheme S, validated and prepared for the pairing once, so that verifying
+/// many signatures against it skips that work
+pub struct PreparedPublicKey<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    pk: PublicKey<G, S, X>,
+    prepared: PKPrepared<G, X>,
+}
+
+/// A signature for scheme S in the group G
+pub struct Signature<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    point: G,
+    _scheme: PhantomData<fn() -> (S, X)>,
+}
+
+/// A message hashed to G under scheme S's ciphersuite, so that signing or verifying it many
+/// times, e.g., under every key in a committee, hashes it only once
+///
+/// Only Basic and Pop messages can be hashed in advance: Aug prepends the signer's public key
+/// to every message, so there the hash depends on the signer.
+pub struct HashedMessage<G, S, X = ExpandMsgXmd<Sha256>>
+where
+    G: BLSSigCore<X>,
+    X: ExpandMsg,
+{
+    point: G,
+    _scheme: PhantomData<fn() -> (S, X)>,
+}
+
+// derive() would require S: Clone, X: Clone, etc., so we implement these by hand
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for SecretKey<G, S, X> {
+    fn clone(&self) -> Self {
+        SecretKey {
+            x_prime: self.x_prime,
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Drop for SecretKey<G, S, X> {
+    fn drop(&mut self) {
+        _wipe_scalar(&mut self.x_prime);
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for SecretKey<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // never print the secret scalar
+        f.write_str("SecretKey(..)")
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for PreparedPublicKey<G, S, X> {
+    fn clone(&self) -> Self {
+        PreparedPublicKey {
+            pk: self.pk,
+            prepared: self.prepared.clone(),
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for PreparedPublicKey<G, S, X> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the prepared form is derived from the key, so there is no need to print it
+        f.debug_tuple("PreparedPublicKey").field(&self.pk).finish()
+    }
+}
+
+macro_rules! point_wrapper_impls {
+    ($name:ident) => {
+        impl<G: BLSSigCore<X>, S, X: ExpandMsg> Clone for $name<G, S, X> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<G: BLSSigCore<X>, S, X: ExpandMsg> Copy for $name<G, S, X> {}
+
+        impl<G: BLSSigCore<X>, S, X: ExpandMsg> PartialEq for $name<G, S, X> {
+            fn eq(&self, other: &Self) -> bool {
+                self.point == other.point
+            }
+        }
+
+        impl<G: BLSSigCore<X>, S, X: ExpandMsg> Eq for $name<G, S, X> {}
+
+        impl<G: BLSSigCore<X>, S, X: ExpandMsg> fmt::Debug for $name<G, S, X> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.point).finish()
+            }
+        }
+    };
+}
+
+point_wrapper_impls!(PublicKey);
+point_wrapper_impls!(Signature);
+point_wrapper_impls!(HashedMessage);
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> SecretKey<G, S, X> {
+    /// Wrap a secret scalar
+    pub fn new(x_prime: ScalarT<G>) -> Self {
+        SecretKey {
+            x_prime,
+            _scheme: PhantomData,
+        }
+    }
+
+    /// Generate a secret key from bytes (see BLSSigCore::keygen)
+    pub fn keygen<B: AsRef<[u8]>>(sk: B) -> Self {
+        SecretKey::new(<G as BLSSigCore<X>>::keygen(sk).0)
+    }
+
+    /// Generate a secret key with the current draft's KeyGen (see BLSSigCore::keygen_with_info)
+    pub fn keygen_with_info<B: AsRef<[u8]>, I: AsRef<[u8]>>(ikm: B, key_info: I) -> Option<Self> {
+        <G as BLSSigCore<X>>::keygen_with_info(ikm, key_info).map(|(x_prime, _)| Self::new(x_prime))
+    }
+
+    /// The secret scalar x_prime
+    ///
+    /// This returns a copy, which is not wiped when the key is dropped.
+    pub fn x_prime(&self) -> ScalarT<G> {
+        self.x_prime
+    }
+
+    /// Decode a secret key from its big-endian encoding
+    ///
+    /// This rejects encodings of the wrong length, integers that are not less than the group
+    /// order, and zero, whose public key would be the identity.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        let mut repr = <ScalarT<G> as PrimeField>::Repr::default();
+        if bytes.len() != repr.as_ref().len() * 8 {
+            return Err(BlsError::InvalidEncoding);
+        }
+        let x_prime = repr
+            .read_be(bytes)
+            .ok()
+            .and_then(|_| ScalarT::<G>::from_repr(repr).ok())
+            .filter(|x_prime| !x_prime.is_zero());
+        repr.zeroize();
+        x_prime.map(SecretKey::new).ok_or(BlsError::InvalidEncoding)
+    }
+
+    /// The 32-byte big-endian encoding of the secret scalar; the caller should wipe it after use
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut repr = self.x_prime.into_repr();
+        let mut ret = [0u8; 32];
+        repr.write_be(&mut ret[..])
+            .expect("the secret scalar is 32 bytes long");
+        repr.zeroize();
+        ret
+    }
+
+    /// The public key g^x_prime
+    pub fn public_key(&self) -> PublicKey<G, S, X> {
+        let mut pk = <PKType<G, X> as CurveProjective>::one();
+        ct_mul_assign(&mut pk, &self.x_prime);
+        PublicKey::new(pk)
+    }
+
+    /// Sign a message
+    pub fn sign<B: AsRef<[u8]>>(&self, msg: B) -> Signature<G, S, X> {
+        // S::sign wipes its copy of the scalar
+        Signature::new(S::sign(self.x_prime, msg))
+    }
+
+    /// Sign a message that was hashed in advance
+    pub fn sign_hashed(&self, msg: &HashedMessage<G, S, X>) -> Signature<G, S, X> {
+        // core_sign_hashed wipes its copy of the scalar
+        Signature::new(<G as BLSSigCore<X>>::core_sign_hashed(
+            self.x_prime,
+            msg.point,
+        ))
+    }
+}
+
+impl<G, S, X> SecretKey<G, S, X>
+where
+    G: BLSSigCore<X> + CurveProjective<Scalar = Fr>,
+    S: Scheme<G, X>,
+    X: ExpandMsg,
+{
+    /// The EIP-2333 master key for a seed of at least 32 bytes (see derive_master_sk)
+    pub fn derive_master<B: AsRef<[u8]>>(seed: B) -> Option<Self> {
+        derive_master_sk(seed).map(Self::new)
+    }
+
+    /// The index'th EIP-2333 child of this key (see derive_child_sk)
+    pub fn derive_child(&self, index: u32) -> Self {
+        Self::new(derive_child_sk(&self.x_prime, index))
+    }
+
+    /// The key at an EIP-2334 path like `m/12381/3600/0/0/0`, starting from the master key for seed
+    pub fn derive_path<B: AsRef<[u8]>>(seed: B, path: &str) -> Result<Self, BlsError> {
+        derive_path(seed, path).map(Self::new)
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> SecretKey<G, Pop, X> {
+    /// Prove possession of this secret key
+    pub fn pop_prove(&self) -> Signature<G, Pop, X> {
+        let pk = self.public_key();
+        Signature::new(_pop_prove::<G, X>(
+            &self.x_prime,
+            &pk.point,
+            <G as BLSSignaturePop<X>>::CSUITE_POP,
+        ))
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> PublicKey<G, S, X> {
+    /// Wrap a public key point
+    pub fn new(point: PKType<G, X>) -> Self {
+        PublicKey {
+            point,
+            _scheme: PhantomData,
+        }
+    }
+
+    /// The underlying group element
+    pub fn point(&self) -> PKType<G, X> {
+        self.point
+    }
+
+    /// Decode a compressed public key
+    ///
+    /// This makes the same checks as Signature::from_bytes. It does not reject the identity:
+    /// use key_validate for that, or rely on the verification functions, which call it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        PKType::<G, X>::from_compressed(bytes).map(PublicKey::new)
+    }
+
+    /// Decode an uncompressed public key, with the same checks as from_bytes
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        PKType::<G, X>::from_uncompressed(bytes).map(PublicKey::new)
+    }
+
+    /// The compressed encoding of this public key (96 bytes in G2, 48 in G1)
+    pub fn to_bytes(&self) -> <PKType<G, X> as PointEncoding>::Compressed {
+        self.point.to_compressed()
+    }
+
+    /// The uncompressed encoding of this public key (192 bytes in G2, 96 in G1)
+    pub fn to_uncompressed_bytes(&self) -> <PKType<G, X> as PointEncoding>::Uncompressed {
+        self.point.to_uncompressed()
+    }
+
+    /// Check that this public key is valid (see BLSSigCore::key_validate)
+    pub fn key_validate(&self) -> bool {
+        self.try_key_validate().is_ok()
+    }
+
+    /// like key_validate, but returns the reason for rejection
+    pub fn try_key_validate(&self) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_key_validate(&self.point)
+    }
+
+    /// Verify a signature on msg under this public key
+    pub fn verify<B: AsRef<[u8]>>(&self, msg: B, sig: &Signature<G, S, X>) -> bool {
+        sig.try_verify(self, msg).is_ok()
+    }
+
+    /// Verify a signature on a message that was hashed in advance
+    pub fn verify_hashed(&self, msg: &HashedMessage<G, S, X>, sig: &Signature<G, S, X>) -> bool {
+        sig.try_verify_hashed(self, msg).is_ok()
+    }
+
+    /// Validate this public key and prepare it for verifying many signatures
+    pub fn prepare(&self) -> Result<PreparedPublicKey<G, S, X>, BlsError> {
+        PreparedPublicKey::new(self)
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> PreparedPublicKey<G, S, X> {
+    /// Validate pk (see BLSSigCore::key_validate), then prepare it for the pairing
+    pub fn new(pk: &PublicKey<G, S, X>) -> Result<Self, BlsError> {
+        pk.try_key_validate()?;
+        Ok(PreparedPublicKey {
+            pk: *pk,
+            prepared: pk.point.into_affine().prepare(),
+        })
+    }
+
+    /// The public key this was prepared from
+    pub fn public_key(&self) -> PublicKey<G, S, X> {
+        self.pk
+    }
+
+    /// Verify a signature on msg under this public key
+    pub fn verify<B: AsRef<[u8]>>(&self, msg: B, sig: &Signature<G, S, X>) -> bool {
+        self.try_verify(msg, sig).is_ok()
+    }
+
+    /// like verify, but returns the reason for rejection
+    pub fn try_verify<B: AsRef<[u8]>>(
+        &self,
+        msg: B,
+        sig: &Signature<G, S, X>,
+    ) -> Result<(), BlsError> {
+        // the key was validated when it was prepared
+        S::try_verify_prepared(&self.pk.point, &self.prepared, sig.point, msg)
+    }
+
+    /// Verify a signature on a message that was hashed in advance
+    pub fn verify_hashed(&self, msg: &HashedMessage<G, S, X>, sig: &Signature<G, S, X>) -> bool {
+        self.try_verify_hashed(msg, sig).is_ok()
+    }
+
+    /// like verify_hashed, but returns the reason for rejection
+    pub fn try_verify_hashed(
+        &self,
+        msg: &HashedMessage<G, S, X>,
+        sig: &Signature<G, S, X>,
+    ) -> Result<(), BlsError> {
+        <G as BLSSigCore<X>>::try_core_verify_prepared_hashed(&self.prepared, sig.point, msg.point)
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> PublicKey<G, Pop, X> {
+    /// Validate and aggregate public keys, e.g., once per committee
+    ///
+    /// The keys' proofs of possession must already have been checked.
+    pub fn aggregate(pks: &[Self]) -> Result<Self, BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point).collect();
+        <G as BLSSignaturePop<X>>::try_aggregate_public_keys(&points[..]).map(PublicKey::new)
+    }
+
+    /// Check a proof of possession for this public key
+    pub fn pop_verify(&self, proof: &Signature<G, Pop, X>) -> bool {
+        self.try_pop_verify(proof).is_ok()
+    }
+
+    /// like pop_verify, but returns the reason for rejection
+    pub fn try_pop_verify(&self, proof: &Signature<G, Pop, X>) -> Result<(), BlsError> {
+        <G as BLSSignaturePop<X>>::try_pop_verify(self.point, proof.point)
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> Signature<G, S, X> {
+    /// Wrap a signature point
+    pub fn new(point: G) -> Self {
+        Signature {
+            point,
+            _scheme: PhantomData,
+        }
+    }
+
+    /// The underlying group element
+    pub fn point(&self) -> G {
+        self.point
+    }
+
+    /// Decode a compressed signature
+    ///
+    /// This rejects encodings of the wrong length, non-canonical encodings, points that
+    /// are not on the curve, and points outside the prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        G::from_compressed(bytes).map(Signature::new)
+    }
+
+    /// Decode an uncompressed signature, with the same checks as from_bytes
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, BlsError> {
+        G::from_uncompressed(bytes).map(Signature::new)
+    }
+
+    /// The compressed encoding of this signature (48 bytes in G1, 96 in G2)
+    pub fn to_bytes(&self) -> G::Compressed {
+        self.point.to_compressed()
+    }
+
+    /// The uncompressed encoding of this signature (96 bytes in G1, 192 in G2)
+    pub fn to_uncompressed_bytes(&self) -> G::Uncompressed {
+        self.point.to_uncompressed()
+    }
+
+    /// Aggregate signatures
+    pub fn aggregate(sigs: &[Self]) -> Self {
+        let points: Vec<G> = sigs.iter().map(|s| s.point).collect();
+        Signature::new(<G as BLSSigCore<X>>::aggregate(&points[..]))
+    }
+
+    /// Verify this signature on msg under pk
+    pub fn verify<B: AsRef<[u8]>>(&self, pk: &PublicKey<G, S, X>, msg: B) -> bool {
+        self.try_verify(pk, msg).is_ok()
+    }
+
+    /// like verify, but returns the reason for rejection
+    pub fn try_verify<B: AsRef<[u8]>>(
+        &self,
+        pk: &PublicKey<G, S, X>,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        S::try_verify(pk.point, self.point, msg)
+    }
+
+    /// Verify this signature on a message that was hashed in advance
+    pub fn verify_hashed(&self, pk: &PublicKey<G, S, X>, msg: &HashedMessage<G, S, X>) -> bool {
+        self.try_verify_hashed(pk, msg).is_ok()
+    }
+
+    /// like verify_hashed, but returns the reason for rejection
+    pub fn try_verify_hashed(
+        &self,
+        pk: &PublicKey<G, S, X>,
+        msg: &HashedMessage<G, S, X>,
+    ) -> Result<(), BlsError> {
+        pk.try_key_validate()?;
+        <G as BLSSigCore<X>>::try_core_verify_hashed(pk.point, self.point, msg.point)
+    }
+
+    /// Verify this aggregated signature on msgs under pks
+    pub fn aggregate_verify<B: AsRef<[u8]>>(&self, pks: &[PublicKey<G, S, X>], msgs: &[B]) -> bool {
+        self.try_aggregate_verify(pks, msgs).is_ok()
+    }
+
+    /// like aggregate_verify, but returns the reason for rejection
+    pub fn try_aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, S, X>],
+        msgs: &[B],
+    ) -> Result<(), BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point).collect();
+        S::try_aggregate_verify(&points[..], msgs, self.point)
+    }
+
+    /// Verify many independent signatures, where sigs[i] is on msgs[i] under pks[i]
+    pub fn batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[PublicKey<G, S, X>],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> bool {
+        Self::try_batch_verify(pks, msgs, sigs, rng).is_ok()
+    }
+
+    /// like batch_verify, but returns the reason for rejection
+    pub fn try_batch_verify<B: AsRef<[u8]>, R: RngCore>(
+        pks: &[PublicKey<G, S, X>],
+        msgs: &[B],
+        sigs: &[Self],
+        rng: &mut R,
+    ) -> Result<(), BlsError> {
+        let pk_points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point).collect();
+        let sig_points: Vec<G> = sigs.iter().map(|sig| sig.point).collect();
+        S::try_batch_verify(&pk_points[..], msgs, &sig_points[..], rng)
+    }
+}
+
+impl<G: BLSSignatureBasic<X>, X: ExpandMsg> Signature<G, Basic, X> {
+    /// Verify this aggregated signature on messages that were hashed in advance
+    pub fn aggregate_verify_hashed(
+        &self,
+        pks: &[PublicKey<G, Basic, X>],
+        msgs: &[HashedMessage<G, Basic, X>],
+    ) -> bool {
+        self.try_aggregate_verify_hashed(pks, msgs).is_ok()
+    }
+
+    /// like aggregate_verify_hashed, but returns the reason for rejection
+    ///
+    /// As with aggregate_verify, the messages must be distinct.
+    pub fn try_aggregate_verify_hashed(
+        &self,
+        pks: &[PublicKey<G, Basic, X>],
+        msgs: &[HashedMessage<G, Basic, X>],
+    ) -> Result<(), BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point).collect();
+        let hashes: Vec<G> = msgs.iter().map(|msg| msg.point).collect();
+        _check_agg_inputs(points.len(), hashes.len())?;
+        _keys_validate::<G, X>(&points[..])?;
+        _basic_aggregate_verify_hashed::<G, X>(&points[..], &hashes[..], self.point)
+    }
+}
+
+impl<G: BLSSignaturePop<X>, X: ExpandMsg> Signature<G, Pop, X> {
+    /// Verify this multisignature on msg under pks
+    pub fn multisig_verify<B: AsRef<[u8]>>(&self, pks: &[PublicKey<G, Pop, X>], msg: B) -> bool {
+        self.try_multisig_verify(pks, msg).is_ok()
+    }
+
+    /// like multisig_verify, but returns the reason for rejection
+    pub fn try_multisig_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: B,
+    ) -> Result<(), BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point).collect();
+        <G as BLSSignaturePop<X>>::try_multisig_verify(&points[..], self.point, msg)
+    }
+
+    /// Verify this aggregated signature on messages that were hashed in advance
+    pub fn aggregate_verify_hashed(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msgs: &[HashedMessage<G, Pop, X>],
+    ) -> bool {
+        self.try_aggregate_verify_hashed(pks, msgs).is_ok()
+    }
+
+    /// like aggregate_verify_hashed, but returns the reason for rejection
+    pub fn try_aggregate_verify_hashed(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msgs: &[HashedMessage<G, Pop, X>],
+    ) -> Result<(), BlsError> {
+        let points: Vec<PKType<G, X>> = pks.iter().map(|pk| pk.point).collect();
+        let hashes: Vec<G> = msgs.iter().map(|msg| msg.point).collect();
+        _check_agg_inputs(points.len(), hashes.len())?;
+        _keys_validate::<G, X>(&points[..])?;
+        <G as BLSSigCore<X>>::try_core_aggregate_verify_hashed(&points[..], &hashes[..], self.point)
+    }
+
+    /// Verify this multisignature on a message that was hashed in advance
+    pub fn multisig_verify_hashed(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: &HashedMessage<G, Pop, X>,
+    ) -> bool {
+        self.try_multisig_verify_hashed(pks, msg).is_ok()
+    }
+
+    /// like multisig_verify_hashed, but returns the reason for rejection
+    pub fn try_multisig_verify_hashed(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: &HashedMessage<G, Pop, X>,
+    ) -> Result<(), BlsError> {
+        let apk = PublicKey::aggregate(pks)?;
+        <G as BLSSigCore<X>>::try_core_verify_hashed(apk.point, self.point, msg.point)
+    }
+
+    /// Verify this multisignature on msg under pks (the draft's FastAggregateVerify)
+    pub fn fast_aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: B,
+    ) -> bool {
+        self.try_fast_aggregate_verify(pks, msg).is_ok()
+    }
+
+    /// like fast_aggregate_verify, but returns the reason for rejection
+    pub fn try_fast_aggregate_verify<B: AsRef<[u8]>>(
+        &self,
+        pks: &[PublicKey<G, Pop, X>],
+        msg: B,
+    ) -> Result<(), BlsError> {
+        self.try_multisig_verify(pks, msg)
+    }
+
+    /// Verify this multisignature on msg under a key from PublicKey::aggregate
+    pub fn fast_aggregate_verify_apk<B: AsRef<[u8]>>(
+        &self,
+        apk: &PublicKey<G, Pop, X>,
+        msg: B,
+    ) -> bool {
+        self.try_fast_aggregate_verify_apk(apk, msg).is_ok()
+    }
+
+    /// like fast_aggregate_verify_apk, but returns the reason for rejection
+    pub fn try_fast_aggregate_verify_apk<B: AsRef<[u8]>>(
+        &self,
+        apk: &PublicKey<G, Pop, X>,
+        msg: B,
+    ) -> Result<(), BlsError> {
+        <G as BLSSignaturePop<X>>::try_fast_aggregate_verify_apk(apk.point, self.point, msg)
+    }
+}
+
+impl<G: BLSSigCore<X>, S: Scheme<G, X>, X: ExpandMsg> HashedMessage<G, S, X> {
+    /// The message's hash, a point in G
+    pub fn point(&self) -> G {
+        self.point
+    }
+
+    // wrap a point that was hashed under S's ciphersuite
+    pub(crate) fn from_point(point: G) -> Self {
+        HashedMessage {
+            point,
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<G: BLSSigCore<X>, S: PrehashScheme<G, X>, X: ExpandMsg> HashedMessage<G, S, X> {
+    /// Hash msg under S's ciphersuite
+    pub fn new<B: AsRef<[u8]>>(msg: B) -> Self {
+        Self::from_point(<G as BLSSigCore<X>>::hash_message(msg, S::CSUITE))
+    }
+}
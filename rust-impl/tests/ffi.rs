@@ -0,0 +1,50 @@
+// Build the staticlib, compile tests/ffi_test.c against it and include/bls_sigs_ref.h, then run it
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn test_ffi() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // this binary lives in <target dir>/<profile>/deps; the staticlib goes in <target dir>/<profile>
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap().parent().unwrap();
+    let target_dir = lib_dir.parent().unwrap();
+    let out = lib_dir.join("ffi_test");
+
+    // cargo test does not build the staticlib, so build it for this profile and features;
+    // otherwise the C test would fail to link, or would link whatever archive was left over
+    let mut build = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    build
+        .current_dir(&manifest_dir)
+        .args(["build", "--lib", "--target-dir"])
+        .arg(target_dir);
+    match lib_dir.file_name().unwrap().to_str().unwrap() {
+        "debug" => (),
+        profile => {
+            build.args(["--profile", profile]);
+        }
+    }
+    if cfg!(feature = "parallel") {
+        build.args(["--features", "parallel"]);
+    }
+    let status = build.status().expect("failed to run cargo");
+    assert!(status.success(), "building the staticlib failed");
+
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(cc)
+        .arg(manifest_dir.join("tests").join("ffi_test.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-o")
+        .arg(&out)
+        .arg(lib_dir.join("libbls_sigs_ref.a"))
+        .args(["-lpthread", "-lm", "-ldl"])
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling ffi_test.c failed");
+
+    let status = Command::new(&out).status().unwrap();
+    assert!(status.success(), "ffi_test failed");
+}
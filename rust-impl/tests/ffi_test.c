@@ -0,0 +1,122 @@
+/* exercise the C interface; run by tests/ffi.rs */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "bls_sigs_ref.h"
+
+static int failures = 0;
+
+/* KeyGen needs at least 32 bytes of input keying material */
+#define IKM_ONE ((const uint8_t *)"key one: 32 bytes of ikm, padded")
+#define IKM_TWO ((const uint8_t *)"key two: 32 bytes of ikm, padded")
+#define IKM_LEN 32
+
+#define CHECK_EQ(expr, want)                                                   \
+    do {                                                                       \
+        int got_ = (expr);                                                     \
+        if (got_ != (want)) {                                                  \
+            fprintf(stderr, "%s:%d: %s returned %d, expected %d\n", __FILE__,  \
+                    __LINE__, #expr, got_, (want));                            \
+            failures++;                                                        \
+        }                                                                      \
+    } while (0)
+
+/* keygen, sign, verify, aggregate, and aggregate_verify for one scheme and group */
+#define TEST_SCHEME(prefix, PK_LEN, SIG_LEN)                                   \
+    static void test_##prefix(void) {                                          \
+        const uint8_t *ikms[2] = {IKM_ONE, IKM_TWO};                           \
+        const uint8_t *msgs[2] = {(const uint8_t *)"message one",              \
+                                  (const uint8_t *)"message two"};             \
+        const size_t msg_lens[2] = {11, 11};                                   \
+        uint8_t sks[2][BLS_SECRET_KEY_LEN];                                    \
+        uint8_t pks[2 * PK_LEN];                                               \
+        uint8_t sigs[2 * SIG_LEN];                                             \
+        uint8_t agg[SIG_LEN];                                                  \
+        uint8_t bad[SIG_LEN];                                                  \
+        int i;                                                                 \
+        for (i = 0; i < 2; i++) {                                              \
+            CHECK_EQ(prefix##_keygen(ikms[i], IKM_LEN, sks[i],                 \
+                                     pks + i * PK_LEN),                        \
+                     BLS_OK);                                                  \
+            CHECK_EQ(prefix##_sign(sks[i], msgs[i], msg_lens[i],               \
+                                   sigs + i * SIG_LEN),                        \
+                     BLS_OK);                                                  \
+            CHECK_EQ(prefix##_verify(pks + i * PK_LEN, sigs + i * SIG_LEN,     \
+                                     msgs[i], msg_lens[i]),                    \
+                     BLS_OK);                                                  \
+        }                                                                      \
+        CHECK_EQ(prefix##_verify(pks, sigs, msgs[1], msg_lens[1]),             \
+                 BLS_ERR_INVALID_SIGNATURE);                                   \
+        CHECK_EQ(prefix##_verify(pks, sigs, NULL, 5), BLS_ERR_NULL_POINTER);   \
+        CHECK_EQ(prefix##_keygen(ikms[0], IKM_LEN - 1, sks[0], pks),           \
+                 BLS_ERR_INVALID_SEED);                                        \
+                                                                               \
+        CHECK_EQ(prefix##_aggregate(sigs, 2, agg), BLS_OK);                    \
+        CHECK_EQ(prefix##_aggregate_verify(pks, msgs, msg_lens, 2, agg),       \
+                 BLS_OK);                                                      \
+        CHECK_EQ(prefix##_aggregate_verify(pks, msgs, msg_lens, 2, sigs),      \
+                 BLS_ERR_INVALID_SIGNATURE);                                   \
+        CHECK_EQ(prefix##_aggregate(sigs, 0, agg), BLS_ERR_EMPTY_INPUT);       \
+        /* n * point length overflows */                                       \
+        CHECK_EQ(prefix##_aggregate(sigs, SIZE_MAX, agg),                      \
+                 BLS_ERR_LENGTH_MISMATCH);                                     \
+        CHECK_EQ(prefix##_aggregate_verify(pks, msgs, msg_lens, SIZE_MAX, agg),\
+                 BLS_ERR_LENGTH_MISMATCH);                                     \
+                                                                               \
+        /* clearing the compression bit makes the encoding invalid */          \
+        memcpy(bad, sigs, SIG_LEN);                                            \
+        bad[0] &= 0x7f;                                                        \
+        CHECK_EQ(prefix##_verify(pks, bad, msgs[0], msg_lens[0]),              \
+                 BLS_ERR_INVALID_ENCODING);                                    \
+        /* secret keys must be less than the group order */                    \
+        memset(bad, 0xff, BLS_SECRET_KEY_LEN);                                 \
+        CHECK_EQ(prefix##_sign(bad, msgs[0], msg_lens[0], agg),                \
+                 BLS_ERR_INVALID_ENCODING);                                    \
+        /* ... and nonzero */                                                  \
+        memset(bad, 0, BLS_SECRET_KEY_LEN);                                    \
+        CHECK_EQ(prefix##_sign(bad, msgs[0], msg_lens[0], agg),                \
+                 BLS_ERR_INVALID_ENCODING);                                    \
+    }
+
+TEST_SCHEME(bls_basic_g1, BLS_G2_LEN, BLS_G1_LEN)
+TEST_SCHEME(bls_basic_g2, BLS_G1_LEN, BLS_G2_LEN)
+TEST_SCHEME(bls_aug_g1, BLS_G2_LEN, BLS_G1_LEN)
+TEST_SCHEME(bls_aug_g2, BLS_G1_LEN, BLS_G2_LEN)
+TEST_SCHEME(bls_pop_g1, BLS_G2_LEN, BLS_G1_LEN)
+TEST_SCHEME(bls_pop_g2, BLS_G1_LEN, BLS_G2_LEN)
+
+#define TEST_POP(prefix, PK_LEN, SIG_LEN)                                      \
+    static void test_##prefix##_pop(void) {                                    \
+        uint8_t sk[BLS_SECRET_KEY_LEN];                                        \
+        uint8_t pk[PK_LEN];                                                    \
+        uint8_t other_pk[PK_LEN];                                              \
+        uint8_t pop[SIG_LEN];                                                  \
+        CHECK_EQ(prefix##_keygen(IKM_ONE, IKM_LEN, sk, pk),                    \
+                 BLS_OK);                                                      \
+        CHECK_EQ(prefix##_pop_prove(sk, pop), BLS_OK);                         \
+        CHECK_EQ(prefix##_pop_verify(pk, pop), BLS_OK);                        \
+        CHECK_EQ(prefix##_keygen(IKM_TWO, IKM_LEN, sk, other_pk),              \
+                 BLS_OK);                                                      \
+        CHECK_EQ(prefix##_pop_verify(other_pk, pop),                           \
+                 BLS_ERR_INVALID_SIGNATURE);                                   \
+    }
+
+TEST_POP(bls_pop_g1, BLS_G2_LEN, BLS_G1_LEN)
+TEST_POP(bls_pop_g2, BLS_G1_LEN, BLS_G2_LEN)
+
+int main(void) {
+    test_bls_basic_g1();
+    test_bls_basic_g2();
+    test_bls_aug_g1();
+    test_bls_aug_g2();
+    test_bls_pop_g1();
+    test_bls_pop_g2();
+    test_bls_pop_g1_pop();
+    test_bls_pop_g2_pop();
+    if (failures) {
+        fprintf(stderr, "%d failures\n", failures);
+        return 1;
+    }
+    return 0;
+}